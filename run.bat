@@ -1,5 +1,7 @@
 @echo off
+set GAME_DIR=C:\Program Files (x86)\Steam\steamapps\common\Battleborn\Binaries\Win64
 cargo build
-start /b "" "C:\Program Files (x86)\Steam\steamapps\common\Battleborn\Binaries\Win64\Battleborn.exe"
+copy /y profiles.json "%GAME_DIR%\profiles.json"
+start /b "" "%GAME_DIR%\Battleborn.exe"
 timeout /t 5 /nobreak
-.\Injector.exe --process-name Battleborn.exe --inject target/x86_64-pc-windows-msvc/debug/reborn_prod.dll
\ No newline at end of file
+.\Injector.exe --process-name Battleborn.exe --inject target/x86_64-pc-windows-msvc/debug/reborn_prod.dll
@@ -0,0 +1,55 @@
+#![allow(clippy::needless_return)]
+
+use std::env;
+use std::path::Path;
+use std::process::ExitCode;
+
+use reborn_prod::registry::ObjectRegistry;
+use reborn_prod::sdk::{SdkGenerator, SdkPackage, write_sdk};
+use reborn_prod::snapshot::GameSnapshot;
+
+/**
+ * Generates the Rust SDK from a snapshot saved in game with reborn snapshot, the game does not have to be running
+ * e.g. sdkgen reflection.snapshot sdk, the directory defaults to sdk
+ */
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().collect();
+
+    let snapshot_path: &str = match args.get(1) {
+        Some(path) => path,
+        None => {
+            println!("Usage: sdkgen <snapshot file> [sdk directory]");
+            return ExitCode::FAILURE;
+        }
+    };
+    let dir: &str = args.get(2).map(|dir| dir.as_str()).unwrap_or("sdk");
+
+    let snapshot: GameSnapshot = match GameSnapshot::load(Path::new(snapshot_path)) {
+        Ok(snapshot) => snapshot,
+        Err(error) => {
+            println!("Unable to read snapshot {}: {}", snapshot_path, error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let registry: ObjectRegistry = match ObjectRegistry::build(&snapshot.memory, snapshot.gnames, snapshot.gobjects) {
+        Ok(registry) => registry,
+        Err(error) => {
+            println!("Unable to read objects from {}: {}", snapshot_path, error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let packages: Vec<SdkPackage> = SdkGenerator::new(&snapshot.memory, snapshot.gnames, &registry).generate();
+
+    return match write_sdk(&packages, Path::new(dir)) {
+        Ok(()) => {
+            println!("Wrote {} SDK packages to {}", packages.len(), dir);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            println!("Unable to write SDK to {}: {}", dir, error);
+            ExitCode::FAILURE
+        }
+    };
+}
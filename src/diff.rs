@@ -0,0 +1,246 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dump::{ObjectRecord, ReflectionDump};
+
+/**
+ * An object that exists in both dumps but under a different class or outer
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ObjectChange {
+    pub old_path: String,
+    pub new_path: String,
+    pub old_class: String,
+    pub new_class: String,
+    pub old_outer: Option<String>,
+    pub new_outer: Option<String>
+}
+
+/**
+ * Difference between two reflection dumps, e.g. before and after a level load or a game update
+ * Every list is sorted so two reports over the same dumps are identical
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct DumpDiff {
+    pub added_objects: Vec<String>,
+    pub removed_objects: Vec<String>,
+    pub changed_objects: Vec<ObjectChange>,
+    pub added_classes: Vec<String>,
+    pub removed_classes: Vec<String>,
+    pub added_functions: Vec<String>,
+    pub removed_functions: Vec<String>
+}
+
+impl DumpDiff {
+    /**
+     * Objects are matched by path first, objects with the same path but a different class are changed
+     * Objects left over on both sides are then paired when they have the same class and short name and their outer chains differ in exactly one renamed link, those moved along with a renamed outer
+     * A leftover that could pair with more than one object is not paired at all, whatever is still unmatched was added or removed
+     */
+    pub fn compare(old: &ReflectionDump, new: &ReflectionDump) -> DumpDiff {
+        let mut diff: DumpDiff = DumpDiff::default();
+
+        let old_objects: HashMap<&str, &ObjectRecord> = old.objects.iter().map(|object| (object.path.as_str(), object)).collect();
+        let new_objects: HashMap<&str, &ObjectRecord> = new.objects.iter().map(|object| (object.path.as_str(), object)).collect();
+
+        let mut old_only: Vec<&ObjectRecord> = Vec::new();
+
+        for object in old.objects.iter() {
+            match new_objects.get(object.path.as_str()) {
+                Some(new_object) => {
+                    if new_object.class != object.class {
+                        diff.changed_objects.push(ObjectChange::between(object, new_object));
+                    }
+                }
+                None => old_only.push(object)
+            }
+        }
+
+        let new_only: Vec<&ObjectRecord> = new.objects.iter().filter(|object| !old_objects.contains_key(object.path.as_str())).collect();
+        let mut candidates: HashMap<RenameKey, Vec<usize>> = HashMap::new();
+        let mut paired: Vec<bool> = vec![false; new_only.len()];
+
+        for (idx, object) in new_only.iter().enumerate() {
+            candidates.entry(rename_key(object)).or_default().push(idx);
+        }
+
+        for object in old_only {
+            let matches: Vec<usize> = candidates.get(&rename_key(object)).into_iter().flatten().copied().filter(|idx| !paired[*idx] && renamed_links(&object.path, &new_only[*idx].path) == 1).collect();
+
+            if matches.len() == 1 {
+                paired[matches[0]] = true;
+                diff.changed_objects.push(ObjectChange::between(object, new_only[matches[0]]));
+            }
+            else {
+                diff.removed_objects.push(object.path.clone());
+            }
+        }
+
+        diff.added_objects = new_only.iter().zip(paired.iter()).filter(|(_, paired)| !**paired).map(|(object, _)| object.path.clone()).collect();
+
+        let old_classes: BTreeSet<&str> = old.classes.iter().map(|class| class.path.as_str()).collect();
+        let new_classes: BTreeSet<&str> = new.classes.iter().map(|class| class.path.as_str()).collect();
+        diff.added_classes = new_classes.difference(&old_classes).map(|path| path.to_string()).collect();
+        diff.removed_classes = old_classes.difference(&new_classes).map(|path| path.to_string()).collect();
+
+        let old_functions: BTreeSet<&str> = old.functions.iter().map(|function| function.path.as_str()).collect();
+        let new_functions: BTreeSet<&str> = new.functions.iter().map(|function| function.path.as_str()).collect();
+        diff.added_functions = new_functions.difference(&old_functions).map(|path| path.to_string()).collect();
+        diff.removed_functions = old_functions.difference(&new_functions).map(|path| path.to_string()).collect();
+
+        diff.added_objects.sort();
+        diff.removed_objects.sort();
+        diff.changed_objects.sort_by(|a, b| a.new_path.cmp(&b.new_path));
+
+        return diff;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self == &DumpDiff::default();
+    }
+}
+
+impl ObjectChange {
+    fn between(old: &ObjectRecord, new: &ObjectRecord) -> ObjectChange {
+        return ObjectChange {
+            old_path: old.path.clone(),
+            new_path: new.path.clone(),
+            old_class: old.class.clone(),
+            new_class: new.class.clone(),
+            old_outer: old.outer.clone(),
+            new_outer: new.outer.clone()
+        };
+    }
+}
+
+/**
+ * Plain text report, one line per entry, + for added, - for removed and ~ for changed, so it greps and diffs like the dumps themselves
+ */
+impl fmt::Display for DumpDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# objects +{} -{} ~{}", self.added_objects.len(), self.removed_objects.len(), self.changed_objects.len())?;
+
+        for path in self.added_objects.iter() {
+            writeln!(f, "+ {}", path)?;
+        }
+        for path in self.removed_objects.iter() {
+            writeln!(f, "- {}", path)?;
+        }
+        for change in self.changed_objects.iter() {
+            if change.old_class != change.new_class {
+                writeln!(f, "~ {} class {} -> {}", change.new_path, change.old_class, change.new_class)?;
+            }
+            if change.old_path != change.new_path {
+                writeln!(f, "~ {} outer {} -> {}", change.new_path, change.old_outer.as_deref().unwrap_or("None"), change.new_outer.as_deref().unwrap_or("None"))?;
+            }
+        }
+
+        writeln!(f, "# classes +{} -{}", self.added_classes.len(), self.removed_classes.len())?;
+
+        for path in self.added_classes.iter() {
+            writeln!(f, "+ {}", path)?;
+        }
+        for path in self.removed_classes.iter() {
+            writeln!(f, "- {}", path)?;
+        }
+
+        writeln!(f, "# functions +{} -{}", self.added_functions.len(), self.removed_functions.len())?;
+
+        for path in self.added_functions.iter() {
+            writeln!(f, "+ {}", path)?;
+        }
+        for path in self.removed_functions.iter() {
+            writeln!(f, "- {}", path)?;
+        }
+
+        return Ok(());
+    }
+}
+
+/**
+ * Class, short name and depth, a leftover can only have moved along with a renamed outer if all three stay the same
+ */
+type RenameKey = (String, String, usize);
+
+fn rename_key(object: &ObjectRecord) -> RenameKey {
+    let links: Vec<&str> = path_links(&object.path);
+
+    return (object.class.to_lowercase(), links.last().unwrap_or(&"").to_lowercase(), links.len());
+}
+
+fn path_links(path: &str) -> Vec<&str> {
+    return path.split(['.', ':']).collect();
+}
+
+/**
+ * How many links of two equally deep paths differ, compared case insensitively like engine names
+ */
+fn renamed_links(old_path: &str, new_path: &str) -> usize {
+    return path_links(old_path).iter().zip(path_links(new_path).iter()).filter(|(old_link, new_link)| !old_link.eq_ignore_ascii_case(new_link)).count();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dump::DUMP_SCHEMA_VERSION;
+
+    fn dump(objects: &[(u32, &str, &str)]) -> ReflectionDump {
+        return ReflectionDump {
+            schema_version: DUMP_SCHEMA_VERSION,
+            names: Vec::new(),
+            objects: objects.iter().map(|(index, path, class)| ObjectRecord { index: *index, address: String::new(), path: path.to_string(), class: class.to_string(), outer: None, flags: 0 }).collect(),
+            classes: Vec::new(),
+            properties: Vec::new(),
+            functions: Vec::new(),
+            enums: Vec::new()
+        };
+    }
+
+    #[test]
+    fn objects_follow_a_renamed_outer() {
+        let old: ReflectionDump = dump(&[(1, "Level.Actor_0", "Core.Package"), (2, "Level.Actor_0.Mesh", "Engine.MeshComponent")]);
+        let new: ReflectionDump = dump(&[(1, "Level.Actor_1", "Core.Package"), (7, "Level.Actor_1.Mesh", "Engine.MeshComponent")]);
+        let diff: DumpDiff = DumpDiff::compare(&old, &new);
+
+        assert_eq!(diff.changed_objects.len(), 1);
+        assert_eq!(diff.changed_objects[0].old_path, "Level.Actor_0.Mesh");
+        assert_eq!(diff.changed_objects[0].new_path, "Level.Actor_1.Mesh");
+        assert_eq!(diff.added_objects, vec!["Level.Actor_1"]);
+        assert_eq!(diff.removed_objects, vec!["Level.Actor_0"]);
+    }
+
+    #[test]
+    fn a_reused_index_is_not_a_move() {
+        let old: ReflectionDump = dump(&[(5, "Level.Pawn_0.Controller", "Engine.Controller")]);
+        let new: ReflectionDump = dump(&[(5, "Other.Hud_3.Controller", "Engine.Controller")]);
+        let diff: DumpDiff = DumpDiff::compare(&old, &new);
+
+        assert!(diff.changed_objects.is_empty());
+        assert_eq!(diff.added_objects, vec!["Other.Hud_3.Controller"]);
+        assert_eq!(diff.removed_objects, vec!["Level.Pawn_0.Controller"]);
+    }
+
+    #[test]
+    fn different_classes_and_ambiguous_moves_are_not_paired() {
+        let old: ReflectionDump = dump(&[(1, "Level.A.Mesh", "Engine.MeshComponent"), (2, "Level.B.Light", "Engine.LightComponent")]);
+        let new: ReflectionDump = dump(&[(1, "Level.C.Mesh", "Engine.MeshComponent"), (3, "Level.D.Mesh", "Engine.MeshComponent"), (2, "Level.E.Light", "Engine.PointLightComponent")]);
+        let diff: DumpDiff = DumpDiff::compare(&old, &new);
+
+        assert!(diff.changed_objects.is_empty());
+        assert_eq!(diff.removed_objects, vec!["Level.A.Mesh", "Level.B.Light"]);
+        assert_eq!(diff.added_objects, vec!["Level.C.Mesh", "Level.D.Mesh", "Level.E.Light"]);
+    }
+
+    #[test]
+    fn same_path_with_a_new_class_is_changed() {
+        let old: ReflectionDump = dump(&[(1, "Level.Thing", "Engine.Actor")]);
+        let new: ReflectionDump = dump(&[(1, "Level.Thing", "Engine.Pawn")]);
+        let diff: DumpDiff = DumpDiff::compare(&old, &new);
+
+        assert_eq!(diff.changed_objects.len(), 1);
+        assert!(diff.added_objects.is_empty() && diff.removed_objects.is_empty());
+        assert!(DumpDiff::compare(&old, &old).is_empty());
+    }
+}
@@ -0,0 +1,314 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ReflectionResult;
+use crate::fields::{PropertyInfo, UEnumRef, UPropertyRef, UStructRef, is_property_class};
+use crate::property::byte_property_enum;
+use crate::flags::ObjectFlags;
+use crate::function::FunctionInfo;
+use crate::memory::MemoryReader;
+use crate::names::NameTable;
+use crate::reflection::{TArray, UObjectRef};
+use crate::registry::{ObjectRegistry, RegistryEntry};
+
+/**
+ * Bumped whenever a field is added, renamed or changes meaning, so tools reading old dumps can tell
+ */
+pub const DUMP_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct NameRecord {
+    pub index: u32,
+    pub name: String
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ObjectRecord {
+    pub index: u32,
+    /**
+     * Hex without a prefix, the same way the log prints addresses
+     */
+    pub address: String,
+    pub path: String,
+    pub class: String,
+    pub outer: Option<String>,
+    /**
+     * Raw ObjectFlags
+     */
+    pub flags: u64
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ClassRecord {
+    pub path: String,
+    /**
+     * class, struct or state
+     */
+    pub kind: String,
+    pub super_class: Option<String>,
+    pub size: i32
+}
+
+/**
+ * A property declared directly on a class, struct or function (params), inherited properties are listed on the class declaring them
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PropertyRecord {
+    pub owner: String,
+    pub name: String,
+    pub class: String,
+    pub offset: usize,
+    pub element_size: usize,
+    pub array_dim: usize,
+    pub flags: u64,
+    /**
+     * The enum a ByteProperty is bound to
+     */
+    pub enum_path: Option<String>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EnumRecord {
+    pub path: String,
+    /**
+     * Value names, the position is the value
+     */
+    pub values: Vec<String>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FunctionRecord {
+    pub owner: String,
+    pub path: String,
+    pub parms_size: usize,
+    pub return_class: Option<String>
+}
+
+/**
+ * Everything the exporters write, collected in one pass so every table describes the same moment
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReflectionDump {
+    pub schema_version: u32,
+    pub names: Vec<NameRecord>,
+    pub objects: Vec<ObjectRecord>,
+    pub classes: Vec<ClassRecord>,
+    pub properties: Vec<PropertyRecord>,
+    pub functions: Vec<FunctionRecord>,
+    pub enums: Vec<EnumRecord>
+}
+
+impl ReflectionDump {
+    /**
+     * Collects the dump out of an already built name table and registry, objects that fail to read are left out
+     */
+    pub fn collect(mem: &dyn MemoryReader, gnames: TArray, names: &NameTable, registry: &ObjectRegistry) -> ReflectionDump {
+        let mut dump: ReflectionDump = ReflectionDump {
+            schema_version: DUMP_SCHEMA_VERSION,
+            names: Vec::new(),
+            objects: Vec::new(),
+            classes: Vec::new(),
+            properties: Vec::new(),
+            functions: Vec::new(),
+            enums: Vec::new()
+        };
+
+        for index in 0..names.len() as u32 {
+            if let Some(name) = names.get(index) {
+                dump.names.push(NameRecord { index: index, name: name.to_string() });
+            }
+        }
+
+        for entry in registry.iter() {
+            let outer: Option<String> = entry.object.outer(mem).ok().flatten().and_then(|outer| registry.get_by_address(outer.address())).map(|outer| outer.path.clone());
+
+            dump.objects.push(ObjectRecord {
+                index: entry.index as u32,
+                address: format!("{:x}", entry.object.address()),
+                path: entry.path.clone(),
+                class: registry.class_path(entry).unwrap_or("").to_string(),
+                outer: outer,
+                flags: entry.flags.0
+            });
+        }
+
+        // Functions can be declared on all three, states redeclare the functions they override
+        for (class_path, kind) in [("Core.Class", "class"), ("Core.ScriptStruct", "struct"), ("Core.State", "state")] {
+            for entry in registry.find_by_class(class_path) {
+                let _ = dump.collect_struct(mem, gnames, registry, entry, kind);
+            }
+        }
+
+        for entry in registry.find_by_class("Core.Enum") {
+            if let Ok(values) = UEnumRef(entry.object).names(mem, gnames) {
+                dump.enums.push(EnumRecord { path: entry.path.clone(), values: values });
+            }
+        }
+
+        return dump;
+    }
+
+    fn collect_struct(&mut self, mem: &dyn MemoryReader, gnames: TArray, registry: &ObjectRegistry, entry: &RegistryEntry, kind: &str) -> ReflectionResult<()> {
+        let ustruct: UStructRef = UStructRef(entry.object);
+
+        self.classes.push(ClassRecord {
+            path: entry.path.clone(),
+            kind: kind.to_string(),
+            super_class: ustruct.super_struct(mem)?.and_then(|super_struct| registry.get_by_address(super_struct.0.address())).map(|super_entry| super_entry.path.clone()),
+            size: ustruct.property_size(mem)?
+        });
+
+        for field in ustruct.children(mem)? {
+            let class_name: String = field.class_name(mem, gnames)?;
+
+            if is_property_class(&class_name) {
+                self.push_property(mem, registry, &entry.path, PropertyInfo::read(mem, gnames, UPropertyRef(field))?)?;
+            }
+            else if class_name == "Function" {
+                self.collect_function(mem, gnames, registry, &entry.path, field)?;
+            }
+        }
+
+        return Ok(());
+    }
+
+    fn collect_function(&mut self, mem: &dyn MemoryReader, gnames: TArray, registry: &ObjectRegistry, owner: &str, function: UObjectRef) -> ReflectionResult<()> {
+        let info: FunctionInfo = FunctionInfo::read(mem, gnames, UStructRef(function))?;
+        let path: String = match registry.get_by_address(function.address()) {
+            Some(entry) => entry.path.clone(),
+            None => function.path_name(mem, gnames)?
+        };
+
+        self.functions.push(FunctionRecord {
+            owner: owner.to_string(),
+            path: path.clone(),
+            parms_size: info.parms_size,
+            return_class: info.return_param().map(|param| param.class_name.clone())
+        });
+
+        for param in info.params {
+            self.push_property(mem, registry, &path, param)?;
+        }
+
+        return Ok(());
+    }
+
+    fn push_property(&mut self, mem: &dyn MemoryReader, registry: &ObjectRegistry, owner: &str, property: PropertyInfo) -> ReflectionResult<()> {
+        let enum_path: Option<String> = match property.class_name.as_str() {
+            "ByteProperty" => byte_property_enum(mem, property.property)?.and_then(|uenum| registry.get_by_address(uenum.0.address())).map(|entry| entry.path.clone()),
+            _ => None
+        };
+
+        self.properties.push(PropertyRecord {
+            owner: owner.to_string(),
+            name: property.name,
+            class: property.class_name,
+            offset: property.offset,
+            element_size: property.element_size,
+            array_dim: property.array_dim,
+            flags: property.flags,
+            enum_path: enum_path
+        });
+
+        return Ok(());
+    }
+
+    /**
+     * Writes dump.json with every table and one CSV per table (names.csv, objects.csv, classes.csv, properties.csv, functions.csv, enums.csv) into dir
+     */
+    pub fn write(&self, dir: &Path) -> std::io::Result<()> {
+        fs::create_dir_all(dir)?;
+
+        fs::write(dir.join("dump.json"), serde_json::to_string_pretty(self)?)?;
+
+        write_csv(&dir.join("names.csv"), &["index", "name"], self.names.iter().map(|name| vec![name.index.to_string(), name.name.clone()]))?;
+
+        write_csv(&dir.join("objects.csv"), &["index", "address", "path", "class", "outer", "flags"], self.objects.iter().map(|object| {
+            vec![object.index.to_string(), object.address.clone(), object.path.clone(), object.class.clone(), object.outer.clone().unwrap_or_default(), ObjectFlags(object.flags).to_string()]
+        }))?;
+
+        write_csv(&dir.join("classes.csv"), &["path", "kind", "super_class", "size"], self.classes.iter().map(|class| {
+            vec![class.path.clone(), class.kind.clone(), class.super_class.clone().unwrap_or_default(), class.size.to_string()]
+        }))?;
+
+        write_csv(&dir.join("properties.csv"), &["owner", "name", "class", "offset", "element_size", "array_dim", "flags", "enum_path"], self.properties.iter().map(|property| {
+            vec![property.owner.clone(), property.name.clone(), property.class.clone(), format!("0x{:x}", property.offset), format!("0x{:x}", property.element_size), property.array_dim.to_string(), format!("0x{:x}", property.flags), property.enum_path.clone().unwrap_or_default()]
+        }))?;
+
+        write_csv(&dir.join("functions.csv"), &["owner", "path", "parms_size", "return_class"], self.functions.iter().map(|function| {
+            vec![function.owner.clone(), function.path.clone(), format!("0x{:x}", function.parms_size), function.return_class.clone().unwrap_or_default()]
+        }))?;
+
+        write_csv(&dir.join("enums.csv"), &["path", "value", "name"], self.enums.iter().flat_map(|uenum| {
+            uenum.values.iter().enumerate().map(|(value, name)| vec![uenum.path.clone(), value.to_string(), name.clone()]).collect::<Vec<Vec<String>>>()
+        }))?;
+
+        return Ok(());
+    }
+
+    /**
+     * Reads back the dump.json written by write
+     */
+    pub fn load(dir: &Path) -> std::io::Result<ReflectionDump> {
+        return Ok(serde_json::from_str(&fs::read_to_string(dir.join("dump.json"))?)?);
+    }
+}
+
+fn write_csv(path: &Path, header: &[&str], rows: impl Iterator<Item = Vec<String>>) -> std::io::Result<()> {
+    let mut csv: String = header.join(",");
+    csv.push('\n');
+
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+
+    return fs::write(path, csv);
+}
+
+/**
+ * Quotes a field if it contains a delimiter, quote or newline, quotes inside are doubled
+ */
+fn csv_field(field: &str) -> String {
+    if !field.contains([',', '"', '\n', '\r']) {
+        return field.to_string();
+    }
+
+    return format!("\"{}\"", field.replace('"', "\"\""));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fields::CPF_RETURN_PARM;
+    use crate::testing::TestImage;
+
+    #[test]
+    fn collects_functions_of_classes_structs_and_states() {
+        let mut image: TestImage = TestImage::new();
+        let game: usize = image.add_package("Game");
+        let script_struct: usize = image.field_class("ScriptStruct");
+        let state: usize = image.field_class("State");
+        let pawn: usize = image.add_class("Pawn", game, 0, 0x60);
+        image.add_property(pawn, "IntProperty", "Health", 0x58, 4, &[]);
+        image.add_function(pawn, "Jump", 0x0);
+        let vector: usize = image.add_struct("Vector", pawn, script_struct, 0, 0xC);
+        let size: usize = image.add_function(vector, "Size", 0x4);
+        image.add_param(size, "FloatProperty", "ReturnValue", 0x0, 4, CPF_RETURN_PARM);
+        let dying: usize = image.add_struct("Dying", pawn, state, 0, 0x60);
+        image.add_function(dying, "Jump", 0x0);
+
+        let names: NameTable = NameTable::build(&image.mem, image.gnames).unwrap();
+        let registry: ObjectRegistry = ObjectRegistry::build(&image.mem, image.gnames, image.gobjects).unwrap();
+        let dump: ReflectionDump = ReflectionDump::collect(&image.mem, image.gnames, &names, &registry);
+
+        assert_eq!(dump.schema_version, 1);
+        assert_eq!(dump.classes.iter().map(|class| (class.path.as_str(), class.kind.as_str())).filter(|(path, _)| path.starts_with("Game")).collect::<Vec<(&str, &str)>>(), vec![("Game.Pawn", "class"), ("Game.Pawn:Vector", "struct"), ("Game.Pawn:Dying", "state")]);
+        assert_eq!(dump.functions.iter().map(|function| (function.owner.as_str(), function.path.as_str())).collect::<Vec<(&str, &str)>>(), vec![("Game.Pawn", "Game.Pawn:Jump"), ("Game.Pawn:Vector", "Game.Pawn:Vector.Size"), ("Game.Pawn:Dying", "Game.Pawn:Dying.Jump")]);
+        assert_eq!(dump.functions[1].return_class.as_deref(), Some("FloatProperty"));
+        assert!(dump.properties.iter().any(|property| property.owner == "Game.Pawn:Vector.Size" && property.name == "ReturnValue"));
+    }
+}
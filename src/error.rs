@@ -0,0 +1,105 @@
+use std::fmt;
+
+/**
+ * Everything that can go wrong while interpreting game memory
+ * Objects are created and destroyed constantly (especially during level transitions), so callers are expected to skip entries that fail instead of bailing out
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReflectionError {
+    /**
+     * A pointer that had to be followed was null, address is where the null pointer was read from
+     */
+    NullPointer { address: usize },
+    /**
+     * An FName index past the end of GNames
+     */
+    NameIndexOutOfRange { index: usize, count: usize },
+    /**
+     * An index past the end of GObjects or another TArray
+     */
+    IndexOutOfRange { index: usize, count: usize },
+    /**
+     * The range could not be read, either it is not mapped or the page is protected
+     */
+    Unreadable { address: usize, len: usize },
+    /**
+     * The range could not be written, either it is not mapped or the page is read only
+     */
+    Unwritable { address: usize, len: usize },
+    /**
+     * A string in memory did not decode, address is the start of the string
+     */
+    InvalidString { address: usize },
+    /**
+     * Following a linked chain (Outer, SuperField, Children/Next) did not end, address is the object the walk started at
+     */
+    ChainTooLong { address: usize },
+    /**
+     * No property with this name exists on the class or struct, or the field with this name is not a property
+     */
+    PropertyNotFound { name: String },
+    /**
+     * No function with this name exists on the class or any of its super classes
+     */
+    FunctionNotFound { name: String },
+    /**
+     * A value of the wrong kind was given for a property, expected describes what the property holds
+     */
+    TypeMismatch { name: String, expected: String },
+    /**
+     * The property's ElementSize does not match the size its class implies, the layout is not what the writer expects
+     */
+    SizeMismatch { name: String, expected: usize, actual: usize },
+    /**
+     * A string or dynamic array does not fit into the buffer the game allocated for it
+     */
+    CapacityExceeded { name: String, needed: usize, capacity: usize },
+    /**
+     * A UFunction was called with more arguments than it has params, or without all of its required ones
+     */
+    ArgumentCount { function: String, expected: usize, given: usize },
+    /**
+     * A TArray header with a negative Num or a Num past Max, address is the header
+     */
+    InvalidArray { address: usize, num: i32, max: i32 },
+    /**
+     * The engine's allocator returned null
+     */
+    AllocationFailed { size: usize },
+    /**
+     * The module at address does not start with valid PE32+ headers
+     */
+    InvalidImage { address: usize },
+    /**
+     * A global (GNames, GObjects) is not located yet, the game called into a hook before injection finished
+     */
+    NotLocated { name: String }
+}
+
+pub type ReflectionResult<T> = Result<T, ReflectionError>;
+
+impl fmt::Display for ReflectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            ReflectionError::NullPointer { address } => write!(f, "null pointer read at {:x}", address),
+            ReflectionError::NameIndexOutOfRange { index, count } => write!(f, "name index {} out of range, GNames has {} entries", index, count),
+            ReflectionError::IndexOutOfRange { index, count } => write!(f, "index {} out of range, array has {} entries", index, count),
+            ReflectionError::Unreadable { address, len } => write!(f, "unable to read {} bytes at {:x}", len, address),
+            ReflectionError::Unwritable { address, len } => write!(f, "unable to write {} bytes at {:x}", len, address),
+            ReflectionError::InvalidString { address } => write!(f, "invalid string data at {:x}", address),
+            ReflectionError::ChainTooLong { address } => write!(f, "chain starting at {:x} does not end", address),
+            ReflectionError::PropertyNotFound { name } => write!(f, "no property named {}", name),
+            ReflectionError::FunctionNotFound { name } => write!(f, "no function named {}", name),
+            ReflectionError::TypeMismatch { name, expected } => write!(f, "property {} expects {}", name, expected),
+            ReflectionError::SizeMismatch { name, expected, actual } => write!(f, "property {} is {} bytes, expected {}", name, actual, expected),
+            ReflectionError::CapacityExceeded { name, needed, capacity } => write!(f, "property {} needs {} elements but only has room for {}", name, needed, capacity),
+            ReflectionError::ArgumentCount { function, expected, given } => write!(f, "{} takes {} arguments, {} given", function, expected, given),
+            ReflectionError::InvalidArray { address, num, max } => write!(f, "TArray at {:x} has Num {} and Max {}", address, num, max),
+            ReflectionError::InvalidImage { address } => write!(f, "no PE32+ image at {:x}", address),
+            ReflectionError::AllocationFailed { size } => write!(f, "engine allocator failed to allocate {} bytes", size),
+            ReflectionError::NotLocated { name } => write!(f, "{} not located yet", name)
+        };
+    }
+}
+
+impl std::error::Error for ReflectionError {}
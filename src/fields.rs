@@ -0,0 +1,402 @@
+use std::sync::OnceLock;
+
+use crate::error::{ReflectionError, ReflectionResult};
+use crate::memory::MemoryReader;
+use crate::names::FName;
+use crate::reflection::{TArray, UObjectRef};
+
+/**
+ * UField/UStruct/UProperty field offsets of the original build (UObject ends at 0x58)
+ */
+const UFIELD_NEXT_OFFSET: usize = 0x58;
+const USTRUCT_SUPERFIELD_OFFSET: usize = 0x70;
+const USTRUCT_CHILDREN_OFFSET: usize = 0x78;
+const USTRUCT_PROPERTYSIZE_OFFSET: usize = 0x80;
+const UPROPERTY_ARRAYDIM_OFFSET: usize = 0x60;
+const UPROPERTY_ELEMENTSIZE_OFFSET: usize = 0x64;
+const UPROPERTY_PROPERTYFLAGS_OFFSET: usize = 0x68;
+const UPROPERTY_OFFSET_OFFSET: usize = 0x88;
+const UENUM_NAMES_OFFSET: usize = 0x60;
+/**
+ * UFunction::ParmsSize, a WORD after FunctionFlags, iNative, RepOffset, FriendlyName, OperPrecedence and NumParms
+ */
+const UFUNCTION_PARMSSIZE_OFFSET: usize = 0x102;
+/**
+ * sizeof(UProperty), the fields UProperty subclasses add start here
+ */
+const UPROPERTY_SIZE: usize = 0xC8;
+
+/**
+ * Where the UField, UStruct, UFunction, UProperty and UEnum fields the reflection reads live, these move between builds like UObjectLayout
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FieldLayout {
+    pub field_next: usize,
+    pub struct_super_field: usize,
+    pub struct_children: usize,
+    pub struct_property_size: usize,
+    pub function_parms_size: usize,
+    pub property_array_dim: usize,
+    pub property_element_size: usize,
+    pub property_flags: usize,
+    pub property_offset: usize,
+    pub property_size: usize,
+    pub enum_names: usize
+}
+
+impl Default for FieldLayout {
+    fn default() -> FieldLayout {
+        return DEFAULT_FIELD_LAYOUT;
+    }
+}
+
+const DEFAULT_FIELD_LAYOUT: FieldLayout = FieldLayout {
+    field_next: UFIELD_NEXT_OFFSET,
+    struct_super_field: USTRUCT_SUPERFIELD_OFFSET,
+    struct_children: USTRUCT_CHILDREN_OFFSET,
+    struct_property_size: USTRUCT_PROPERTYSIZE_OFFSET,
+    function_parms_size: UFUNCTION_PARMSSIZE_OFFSET,
+    property_array_dim: UPROPERTY_ARRAYDIM_OFFSET,
+    property_element_size: UPROPERTY_ELEMENTSIZE_OFFSET,
+    property_flags: UPROPERTY_PROPERTYFLAGS_OFFSET,
+    property_offset: UPROPERTY_OFFSET_OFFSET,
+    property_size: UPROPERTY_SIZE,
+    enum_names: UENUM_NAMES_OFFSET
+};
+
+static FIELD_LAYOUT: OnceLock<FieldLayout> = OnceLock::new();
+
+/**
+ * Sets the layout every UStructRef, UPropertyRef and UEnumRef reads with, only the first call counts like set_uobject_layout
+ * Returns false if a layout was already set
+ */
+pub fn set_field_layout(layout: FieldLayout) -> bool {
+    return FIELD_LAYOUT.set(layout).is_ok();
+}
+
+pub fn field_layout() -> &'static FieldLayout {
+    return FIELD_LAYOUT.get().unwrap_or(&DEFAULT_FIELD_LAYOUT);
+}
+
+/**
+ * Loop guards, no class has anywhere near this many fields or ancestors
+ */
+const MAX_FIELDS: usize = 0x10000;
+pub const MAX_SUPER_DEPTH: usize = 64;
+
+/**
+ * EPropertyFlags
+ */
+pub const CPF_EDIT: u64 = 0x1;
+pub const CPF_CONST: u64 = 0x2;
+pub const CPF_OPTIONAL_PARM: u64 = 0x10;
+pub const CPF_NET: u64 = 0x20;
+pub const CPF_PARM: u64 = 0x80;
+pub const CPF_OUT_PARM: u64 = 0x100;
+pub const CPF_SKIP_PARM: u64 = 0x200;
+pub const CPF_RETURN_PARM: u64 = 0x400;
+pub const CPF_NATIVE: u64 = 0x1000;
+pub const CPF_TRANSIENT: u64 = 0x2000;
+pub const CPF_CONFIG: u64 = 0x4000;
+
+/**
+ * A UStruct (UClass, UScriptStruct, UFunction or UState) in game memory
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct UStructRef(pub UObjectRef);
+
+impl UStructRef {
+    /**
+     * Size of the params buffer ProcessEvent expects, only meaningful on a UFunction
+     */
+    pub fn parms_size(&self, mem: &dyn MemoryReader) -> ReflectionResult<usize> {
+        return Ok(mem.read_u16(self.0.address() + field_layout().function_parms_size)? as usize);
+    }
+
+    /**
+     * The struct this one inherits from, None for UObject itself
+     */
+    pub fn super_struct(&self, mem: &dyn MemoryReader) -> ReflectionResult<Option<UStructRef>> {
+        let address: usize = mem.read_usize(self.0.address() + field_layout().struct_super_field)?;
+
+        if address == 0 {
+            return Ok(None);
+        }
+
+        return Ok(Some(UStructRef(UObjectRef(address))));
+    }
+
+    /**
+     * Size in bytes of an instance, including everything inherited
+     */
+    pub fn property_size(&self, mem: &dyn MemoryReader) -> ReflectionResult<i32> {
+        return mem.read_i32(self.0.address() + field_layout().struct_property_size);
+    }
+
+    /**
+     * This struct followed by every super struct, most derived first
+     */
+    pub fn hierarchy(&self, mem: &dyn MemoryReader) -> ReflectionResult<Vec<UStructRef>> {
+        let mut chain: Vec<UStructRef> = vec![*self];
+        let mut current: Option<UStructRef> = self.super_struct(mem)?;
+
+        while let Some(ustruct) = current {
+            if chain.len() > MAX_SUPER_DEPTH {
+                return Err(ReflectionError::ChainTooLong { address: self.0.address() });
+            }
+
+            chain.push(ustruct);
+            current = ustruct.super_struct(mem)?;
+        }
+
+        return Ok(chain);
+    }
+
+    /**
+     * Whether this struct is parent or inherits from it
+     */
+    pub fn is_child_of(&self, mem: &dyn MemoryReader, parent: UStructRef) -> ReflectionResult<bool> {
+        return Ok(self.hierarchy(mem)?.contains(&parent));
+    }
+
+    /**
+     * Fields declared directly on this struct, following Children then each field's Next
+     */
+    pub fn children(&self, mem: &dyn MemoryReader) -> ReflectionResult<Vec<UObjectRef>> {
+        let mut fields: Vec<UObjectRef> = Vec::new();
+        let mut current: usize = mem.read_usize(self.0.address() + field_layout().struct_children)?;
+
+        while current != 0 {
+            if fields.len() > MAX_FIELDS {
+                return Err(ReflectionError::ChainTooLong { address: self.0.address() });
+            }
+
+            fields.push(UObjectRef(current));
+            current = mem.read_usize(current + field_layout().field_next)?;
+        }
+
+        return Ok(fields);
+    }
+
+    /**
+     * Every field including inherited ones, base class fields first so they come out in memory order
+     */
+    pub fn all_fields(&self, mem: &dyn MemoryReader) -> ReflectionResult<Vec<UObjectRef>> {
+        let mut fields: Vec<UObjectRef> = Vec::new();
+
+        for ustruct in self.hierarchy(mem)?.iter().rev() {
+            fields.extend(ustruct.children(mem)?);
+        }
+
+        return Ok(fields);
+    }
+
+    /**
+     * Every property including inherited ones, functions, enums, consts and nested structs are skipped
+     */
+    pub fn properties(&self, mem: &dyn MemoryReader, gnames: TArray) -> ReflectionResult<Vec<PropertyInfo>> {
+        let mut properties: Vec<PropertyInfo> = Vec::new();
+
+        for field in self.all_fields(mem)? {
+            if is_property_class(&field.class_name(mem, gnames)?) {
+                properties.push(PropertyInfo::read(mem, gnames, UPropertyRef(field))?);
+            }
+        }
+
+        return Ok(properties);
+    }
+
+    /**
+     * Every function declared on this struct or inherited, overrides in subclasses come before the functions they override
+     */
+    pub fn functions(&self, mem: &dyn MemoryReader, gnames: TArray) -> ReflectionResult<Vec<UStructRef>> {
+        let mut functions: Vec<UStructRef> = Vec::new();
+
+        for ustruct in self.hierarchy(mem)? {
+            for field in ustruct.children(mem)? {
+                if field.class_name(mem, gnames)? == "Function" {
+                    functions.push(UStructRef(field));
+                }
+            }
+        }
+
+        return Ok(functions);
+    }
+
+    /**
+     * Finds a field by name, searching this struct first and then each super struct
+     */
+    pub fn find_field(&self, mem: &dyn MemoryReader, gnames: TArray, name: &str) -> ReflectionResult<Option<UObjectRef>> {
+        for ustruct in self.hierarchy(mem)? {
+            for field in ustruct.children(mem)? {
+                if field.name(mem, gnames)?.eq_ignore_ascii_case(name) {
+                    return Ok(Some(field));
+                }
+            }
+        }
+
+        return Ok(None);
+    }
+}
+
+/**
+ * IsA straight from game memory, whether object is an instance of class or of any subclass of it
+ * ObjectRegistry::is_a answers the same without reading memory for registered objects
+ */
+pub fn is_a(mem: &dyn MemoryReader, object: UObjectRef, class: UObjectRef) -> ReflectionResult<bool> {
+    return UStructRef(object.class(mem)?).is_child_of(mem, UStructRef(class));
+}
+
+/**
+ * A UProperty in game memory
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct UPropertyRef(pub UObjectRef);
+
+impl UPropertyRef {
+    pub fn array_dim(&self, mem: &dyn MemoryReader) -> ReflectionResult<i32> {
+        return mem.read_i32(self.0.address() + field_layout().property_array_dim);
+    }
+
+    pub fn element_size(&self, mem: &dyn MemoryReader) -> ReflectionResult<i32> {
+        return mem.read_i32(self.0.address() + field_layout().property_element_size);
+    }
+
+    pub fn property_flags(&self, mem: &dyn MemoryReader) -> ReflectionResult<u64> {
+        return mem.read_u64(self.0.address() + field_layout().property_flags);
+    }
+
+    /**
+     * Offset of the property from the start of the owning object, struct or params buffer
+     */
+    pub fn offset(&self, mem: &dyn MemoryReader) -> ReflectionResult<i32> {
+        return mem.read_i32(self.0.address() + field_layout().property_offset);
+    }
+}
+
+/**
+ * A UEnum in game memory, its values are the indices into Names
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct UEnumRef(pub UObjectRef);
+
+impl UEnumRef {
+    pub fn names_array(&self) -> TArray<FName> {
+        return TArray::new(self.0.address() + field_layout().enum_names);
+    }
+
+    /**
+     * Every value name in order, the last one is usually the generated _MAX entry
+     */
+    pub fn names(&self, mem: &dyn MemoryReader, gnames: TArray) -> ReflectionResult<Vec<String>> {
+        let names: TArray<FName> = self.names_array();
+
+        if names.len(mem)? > MAX_FIELDS {
+            return Err(ReflectionError::ChainTooLong { address: self.0.address() });
+        }
+
+        return names.to_vec(mem)?.iter().map(|name| name.resolve(mem, gnames)).collect();
+    }
+
+    /**
+     * Value of a name, case insensitive like every engine name, e.g. TRAVEL_Absolute is 0 in Engine.Actor.ETravelType
+     */
+    pub fn value_of(&self, mem: &dyn MemoryReader, gnames: TArray, name: &str) -> ReflectionResult<Option<u8>> {
+        return Ok(self.names(mem, gnames)?.iter().position(|value_name| value_name.eq_ignore_ascii_case(name)).map(|value| value as u8));
+    }
+
+    /**
+     * Name of a value, None if the value is past the last name
+     */
+    pub fn name_of(&self, mem: &dyn MemoryReader, gnames: TArray, value: u8) -> ReflectionResult<Option<String>> {
+        return Ok(self.names(mem, gnames)?.get(value as usize).cloned());
+    }
+}
+
+/**
+ * Everything about a property that is needed to find and size it, read in one go
+ */
+#[derive(Clone, Debug)]
+pub struct PropertyInfo {
+    pub property: UPropertyRef,
+    pub name: String,
+    pub class_name: String,
+    pub offset: usize,
+    pub element_size: usize,
+    pub array_dim: usize,
+    pub flags: u64
+}
+
+impl PropertyInfo {
+    pub fn read(mem: &dyn MemoryReader, gnames: TArray, property: UPropertyRef) -> ReflectionResult<PropertyInfo> {
+        return Ok(PropertyInfo {
+            property: property,
+            name: property.0.name(mem, gnames)?,
+            class_name: property.0.class_name(mem, gnames)?,
+            offset: property.offset(mem)?.max(0) as usize,
+            element_size: property.element_size(mem)?.max(0) as usize,
+            array_dim: property.array_dim(mem)?.max(1) as usize,
+            flags: property.property_flags(mem)?
+        });
+    }
+
+    /**
+     * Total size taken up in the owner, static arrays take ArrayDim elements
+     */
+    pub fn size(&self) -> usize {
+        return self.element_size * self.array_dim;
+    }
+
+    pub fn has_flag(&self, flag: u64) -> bool {
+        return self.flags & flag != 0;
+    }
+}
+
+/**
+ * Whether a field class is one of the UProperty subclasses (IntProperty, ObjectProperty, ...)
+ */
+pub fn is_property_class(class_name: &str) -> bool {
+    return class_name.ends_with("Property");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryWriter;
+    use crate::testing::TestImage;
+
+    #[test]
+    fn is_a_follows_the_super_field_chain() {
+        let mut image: TestImage = TestImage::new();
+        let engine: usize = image.add_package("Engine");
+        let poplar: usize = image.add_package("PoplarGame");
+        let controller_class: usize = image.add_class("PlayerController", engine, 0, 0x100);
+        let poplar_class: usize = image.add_class("PoplarPlayerController", poplar, controller_class, 0x100);
+        let sub_class: usize = image.add_class("SubPlayerController", poplar, poplar_class, 0x100);
+        let camera_class: usize = image.add_class("Camera", engine, 0, 0x100);
+        let sub: UObjectRef = UObjectRef(image.add_object("SubPlayerController_0", poplar, sub_class));
+        let poplar_controller: UObjectRef = UObjectRef(image.add_object("PoplarPlayerController_0", poplar, poplar_class));
+
+        assert!(is_a(&image.mem, sub, UObjectRef(controller_class)).unwrap());
+        assert!(is_a(&image.mem, sub, UObjectRef(sub_class)).unwrap());
+        assert!(!is_a(&image.mem, poplar_controller, UObjectRef(sub_class)).unwrap());
+        assert!(!is_a(&image.mem, sub, UObjectRef(camera_class)).unwrap());
+
+        let chain: Vec<usize> = UStructRef(UObjectRef(sub_class)).hierarchy(&image.mem).unwrap().iter().map(|ustruct| ustruct.0.address()).collect();
+        assert_eq!(chain, vec![sub_class, poplar_class, controller_class]);
+    }
+
+    #[test]
+    fn super_field_cycles_are_errors() {
+        let mut image: TestImage = TestImage::new();
+        let engine: usize = image.add_package("Engine");
+        let first: usize = image.add_class("First", engine, 0, 0x100);
+        let second: usize = image.add_class("Second", engine, first, 0x100);
+        let camera_class: usize = image.add_class("Camera", engine, 0, 0x100);
+        image.mem.write_usize(first + field_layout().struct_super_field, second).unwrap();
+        let object: UObjectRef = UObjectRef(image.add_object("First_0", engine, first));
+
+        assert_eq!(UStructRef(UObjectRef(first)).hierarchy(&image.mem), Err(ReflectionError::ChainTooLong { address: first }));
+        assert_eq!(is_a(&image.mem, object, UObjectRef(camera_class)), Err(ReflectionError::ChainTooLong { address: first }));
+    }
+}
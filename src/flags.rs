@@ -0,0 +1,189 @@
+use std::fmt;
+
+/**
+ * EObjectFlags, the 64 bit flag word every UObject carries
+ */
+pub const RF_PROTECTED: u64 = 0x100;
+pub const RF_CLASS_DEFAULT_OBJECT: u64 = 0x200;
+pub const RF_ARCHETYPE_OBJECT: u64 = 0x400;
+pub const RF_ROOT_SET: u64 = 0x4000;
+pub const RF_BEGIN_DESTROYED: u64 = 0x8000;
+pub const RF_FINISH_DESTROYED: u64 = 0x10000;
+pub const RF_TRANSACTIONAL: u64 = 0x100000000;
+pub const RF_UNREACHABLE: u64 = 0x200000000;
+pub const RF_PUBLIC: u64 = 0x400000000;
+pub const RF_NEED_LOAD: u64 = 0x20000000000;
+pub const RF_ASYNC_LOADING: u64 = 0x40000000000;
+pub const RF_TRANSIENT: u64 = 0x400000000000;
+pub const RF_STANDALONE: u64 = 0x8000000000000;
+pub const RF_NATIVE: u64 = 0x400000000000000;
+pub const RF_PENDING_KILL: u64 = 0x2000000000000000;
+
+/**
+ * Every flag that means the object is on its way out of GObjects
+ */
+pub const RF_PENDING_KILL_MASK: u64 = RF_PENDING_KILL | RF_BEGIN_DESTROYED | RF_FINISH_DESTROYED | RF_UNREACHABLE;
+
+/**
+ * Names used in queries and when printing flags, without the RF_ prefix
+ */
+const FLAG_NAMES: [(u64, &str); 15] = [
+    (RF_PROTECTED, "Protected"),
+    (RF_CLASS_DEFAULT_OBJECT, "ClassDefaultObject"),
+    (RF_ARCHETYPE_OBJECT, "ArchetypeObject"),
+    (RF_ROOT_SET, "RootSet"),
+    (RF_BEGIN_DESTROYED, "BeginDestroyed"),
+    (RF_FINISH_DESTROYED, "FinishDestroyed"),
+    (RF_TRANSACTIONAL, "Transactional"),
+    (RF_UNREACHABLE, "Unreachable"),
+    (RF_PUBLIC, "Public"),
+    (RF_NEED_LOAD, "NeedLoad"),
+    (RF_ASYNC_LOADING, "AsyncLoading"),
+    (RF_TRANSIENT, "Transient"),
+    (RF_STANDALONE, "Standalone"),
+    (RF_NATIVE, "Native"),
+    (RF_PENDING_KILL, "PendingKill")
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct ObjectFlags(pub u64);
+
+impl ObjectFlags {
+    pub fn contains(&self, flags: u64) -> bool {
+        return self.0 & flags != 0;
+    }
+
+    /**
+     * The Default__ object of a class
+     */
+    pub fn is_class_default(&self) -> bool {
+        return self.contains(RF_CLASS_DEFAULT_OBJECT);
+    }
+
+    pub fn is_archetype(&self) -> bool {
+        return self.contains(RF_ARCHETYPE_OBJECT);
+    }
+
+    /**
+     * Class default objects and archetypes, the objects new instances are copied from rather than instances themselves
+     */
+    pub fn is_template(&self) -> bool {
+        return self.contains(RF_CLASS_DEFAULT_OBJECT | RF_ARCHETYPE_OBJECT);
+    }
+
+    pub fn is_transient(&self) -> bool {
+        return self.contains(RF_TRANSIENT);
+    }
+
+    /**
+     * Marked for destruction, or already being destroyed, and about to disappear from GObjects
+     */
+    pub fn is_pending_kill(&self) -> bool {
+        return self.contains(RF_PENDING_KILL_MASK);
+    }
+
+    /**
+     * Looks up a flag by name, with or without the RF_ prefix and in any case, e.g. ClassDefaultObject or rf_transient
+     */
+    pub fn parse_flag(name: &str) -> Option<u64> {
+        let name: &str = match name.get(..3) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("RF_") => &name[3..],
+            _ => name
+        };
+
+        return FLAG_NAMES.iter().find(|(_, flag_name)| flag_name.eq_ignore_ascii_case(name)).map(|(flag, _)| *flag);
+    }
+}
+
+/**
+ * Every known flag that is set separated by |, unknown bits are added as hex
+ */
+impl fmt::Display for ObjectFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut remaining: u64 = self.0;
+        let mut first: bool = true;
+
+        for (flag, name) in FLAG_NAMES.iter() {
+            if self.0 & flag == 0 {
+                continue;
+            }
+
+            if !first {
+                write!(f, "|")?;
+            }
+            write!(f, "{}", name)?;
+
+            first = false;
+            remaining &= !flag;
+        }
+
+        if remaining != 0 {
+            if !first {
+                write!(f, "|")?;
+            }
+            write!(f, "0x{:x}", remaining)?;
+        }
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_flag_name() {
+        for (flag, name) in FLAG_NAMES.iter() {
+            assert_eq!(ObjectFlags::parse_flag(name), Some(*flag), "{}", name);
+            assert_eq!(ObjectFlags::parse_flag(&format!("RF_{}", name)), Some(*flag), "RF_{}", name);
+            assert_eq!(ObjectFlags::parse_flag(&name.to_lowercase()), Some(*flag), "{}", name.to_lowercase());
+            assert_eq!(ObjectFlags::parse_flag(&format!("Rf_{}", name.to_uppercase())), Some(*flag), "Rf_{}", name.to_uppercase());
+        }
+
+        assert_eq!(ObjectFlags::parse_flag("ClassDefaultObject"), Some(RF_CLASS_DEFAULT_OBJECT));
+        assert_eq!(ObjectFlags::parse_flag("rf_pendingkill"), Some(RF_PENDING_KILL));
+        assert_eq!(ObjectFlags::parse_flag("Shiny"), None);
+        assert_eq!(ObjectFlags::parse_flag("RF_"), None);
+        assert_eq!(ObjectFlags::parse_flag(""), None);
+        assert_eq!(ObjectFlags::parse_flag("RF_RF_Transient"), None);
+        assert_eq!(ObjectFlags::parse_flag("0x200"), None);
+    }
+
+    #[test]
+    fn helpers_check_their_flags() {
+        assert!(ObjectFlags(RF_CLASS_DEFAULT_OBJECT).is_class_default());
+        assert!(ObjectFlags(RF_CLASS_DEFAULT_OBJECT).is_template());
+        assert!(!ObjectFlags(RF_CLASS_DEFAULT_OBJECT).is_archetype());
+        assert!(ObjectFlags(RF_ARCHETYPE_OBJECT | RF_PUBLIC).is_archetype());
+        assert!(ObjectFlags(RF_ARCHETYPE_OBJECT).is_template());
+        assert!(ObjectFlags(RF_TRANSIENT).is_transient());
+        assert!(!ObjectFlags(RF_PUBLIC | RF_NATIVE).is_template());
+        assert!(!ObjectFlags::default().contains(u64::MAX));
+
+        for flag in [RF_PENDING_KILL, RF_BEGIN_DESTROYED, RF_FINISH_DESTROYED, RF_UNREACHABLE] {
+            assert!(ObjectFlags(flag).is_pending_kill());
+            assert_eq!(RF_PENDING_KILL_MASK & flag, flag);
+        }
+
+        assert_eq!(RF_PENDING_KILL_MASK.count_ones(), 4);
+        assert!(!ObjectFlags(RF_ROOT_SET | RF_STANDALONE | RF_NEED_LOAD).is_pending_kill());
+    }
+
+    #[test]
+    fn display_round_trips_through_parse_flag() {
+        let all: u64 = FLAG_NAMES.iter().fold(0, |flags, (flag, _)| flags | flag);
+
+        for flags in [RF_PUBLIC, RF_CLASS_DEFAULT_OBJECT | RF_PUBLIC | RF_TRANSIENT, RF_PENDING_KILL_MASK, all] {
+            let text: String = ObjectFlags(flags).to_string();
+            let parsed: u64 = text.split('|').map(|name| ObjectFlags::parse_flag(name).unwrap()).fold(0, |parsed, flag| parsed | flag);
+
+            assert_eq!(parsed, flags, "{}", text);
+        }
+
+        assert_eq!(ObjectFlags(0).to_string(), "");
+        assert_eq!(ObjectFlags(RF_PUBLIC | RF_CLASS_DEFAULT_OBJECT).to_string(), "ClassDefaultObject|Public");
+        assert_eq!(ObjectFlags(RF_PUBLIC | 0x3).to_string(), "Public|0x3");
+        assert_eq!(ObjectFlags(0x1).to_string(), "0x1");
+    }
+}
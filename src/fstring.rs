@@ -0,0 +1,232 @@
+use crate::error::{ReflectionError, ReflectionResult};
+use crate::memory::MemoryReader;
+use crate::reflection::{TARRAY_SIZE, TArray};
+
+/**
+ * Longest FString that is read, anything longer is treated as garbage rather than allocated
+ */
+pub const MAX_FSTRING_LEN: usize = 0x10000;
+
+/**
+ * A TArray of UTF-16 code units, Num counts the terminator so an empty string is either Num 0 or just the terminator
+ */
+pub type FString = TArray<u16>;
+
+/**
+ * UTF-16 with a terminator, the layout FString data and TCHAR* arguments (console commands) use
+ */
+pub fn to_utf16z(text: &str) -> Vec<u16> {
+    return text.encode_utf16().chain(std::iter::once(0)).collect();
+}
+
+/**
+ * The code units an FString holding text stores, nothing at all for the empty string since the engine leaves those at Num 0 rather than a lone terminator
+ * Every FString this crate writes goes through here, property::write_value for engine owned strings and TempFString for params
+ */
+pub fn fstring_units(text: &str) -> Vec<u16> {
+    if text.is_empty() {
+        return Vec::new();
+    }
+
+    return to_utf16z(text);
+}
+
+/**
+ * Decodes UTF-16 up to the first terminator, None if the units are not valid UTF-16
+ */
+pub fn from_utf16z(units: &[u16]) -> Option<String> {
+    let end: usize = units.iter().position(|unit| *unit == 0).unwrap_or(units.len());
+
+    return String::from_utf16(&units[..end]).ok();
+}
+
+impl TArray<u16> {
+    /**
+     * Reads an engine owned string, a null Data pointer or Num 0 is the empty string
+     */
+    pub fn read_string(&self, mem: &dyn MemoryReader) -> ReflectionResult<String> {
+        let data: usize = self.data(mem)?;
+        let count: usize = self.len(mem)?;
+
+        if data == 0 || count == 0 {
+            return Ok(String::new());
+        }
+
+        if count > MAX_FSTRING_LEN {
+            return Err(ReflectionError::InvalidString { address: data });
+        }
+
+        return from_utf16z(&self.to_vec(mem)?).ok_or(ReflectionError::InvalidString { address: data });
+    }
+}
+
+/**
+ * An FString owned by the DLL, for strings the game only reads for the duration of a call (UFunction params like NavToURL's URL)
+ * The engine never frees it, so it must not be stored anywhere the game keeps it, property::write_value moves strings the game keeps into engine allocations
+ */
+pub struct TempFString {
+    units: Vec<u16>
+}
+
+impl TempFString {
+    pub fn new(text: &str) -> TempFString {
+        return TempFString { units: fstring_units(text) };
+    }
+
+    /**
+     * Address of the first code unit, 0 for the empty string the same way the engine leaves Data null
+     */
+    pub fn address(&self) -> usize {
+        if self.units.is_empty() {
+            return 0;
+        }
+
+        return self.units.as_ptr() as usize;
+    }
+
+    pub fn units(&self) -> &[u16] {
+        return &self.units;
+    }
+
+    /**
+     * The TArray header (Data, Num, Max) pointing at the string, to be copied into a params buffer
+     * Max is Num so the engine never tries to append in place
+     */
+    pub fn header(&self) -> [u8; TARRAY_SIZE] {
+        let mut header: [u8; TARRAY_SIZE] = [0u8; TARRAY_SIZE];
+
+        header[0x0..0x8].copy_from_slice(&(self.address() as u64).to_le_bytes());
+        header[0x8..0xC].copy_from_slice(&(self.units.len() as i32).to_le_bytes());
+        header[0xC..0x10].copy_from_slice(&(self.units.len() as i32).to_le_bytes());
+
+        return header;
+    }
+
+    /**
+     * Copies the string into buf if the range lies inside it, lets a reader over a params buffer follow the header back to the text
+     */
+    pub fn read_bytes(&self, address: usize, buf: &mut [u8]) -> bool {
+        let offset: usize = match address.checked_sub(self.address()) {
+            Some(offset) if self.address() != 0 => offset,
+            _ => return false
+        };
+
+        let bytes: Vec<u8> = self.units.iter().flat_map(|unit| unit.to_le_bytes()).collect();
+
+        let end: usize = match offset.checked_add(buf.len()) {
+            Some(end) if end <= bytes.len() => end,
+            _ => return false
+        };
+
+        buf.copy_from_slice(&bytes[offset..end]);
+
+        return true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryWriter;
+    use crate::testing::TestImage;
+
+    /**
+     * An FString header pointing at units, Num and Max both the number of units
+     */
+    fn raw_fstring(image: &mut TestImage, units: &[u16]) -> FString {
+        let header: usize = image.alloc(TARRAY_SIZE);
+        let data: usize = image.alloc(units.len().max(1) * 2);
+
+        image.mem.write_bytes(data, &units.iter().flat_map(|unit| unit.to_le_bytes()).collect::<Vec<u8>>());
+        image.write_array(header, data, units.len(), units.len());
+
+        return FString::new(header);
+    }
+
+    #[test]
+    fn empty_strings_read_as_empty() {
+        let mut image: TestImage = TestImage::new();
+
+        let null_data: usize = image.alloc(TARRAY_SIZE);
+        image.write_array(null_data, 0, 4, 4);
+        assert_eq!(FString::new(null_data).read_string(&image.mem).unwrap(), "");
+
+        let no_units: FString = raw_fstring(&mut image, &[]);
+        assert_eq!(no_units.read_string(&image.mem).unwrap(), "");
+
+        let terminator_only: FString = raw_fstring(&mut image, &[0]);
+        assert_eq!(terminator_only.read_string(&image.mem).unwrap(), "");
+
+        let header: usize = image.alloc(TARRAY_SIZE);
+        image.write_fstring(header, "Slums_P", 0x20);
+        assert_eq!(FString::new(header).read_string(&image.mem).unwrap(), "Slums_P");
+    }
+
+    #[test]
+    fn long_and_invalid_strings_are_errors() {
+        let mut image: TestImage = TestImage::new();
+
+        let mut units: Vec<u16> = vec![u16::from(b'a'); MAX_FSTRING_LEN];
+        *units.last_mut().unwrap() = 0;
+        let longest: FString = raw_fstring(&mut image, &units);
+        assert_eq!(longest.read_string(&image.mem).unwrap().len(), MAX_FSTRING_LEN - 1);
+
+        units.push(0);
+        let too_long: FString = raw_fstring(&mut image, &units);
+        let data: usize = too_long.data(&image.mem).unwrap();
+        assert_eq!(too_long.read_string(&image.mem), Err(ReflectionError::InvalidString { address: data }));
+
+        let lone_surrogate: FString = raw_fstring(&mut image, &[u16::from(b'a'), 0xD800, 0]);
+        let data: usize = lone_surrogate.data(&image.mem).unwrap();
+        assert_eq!(lone_surrogate.read_string(&image.mem), Err(ReflectionError::InvalidString { address: data }));
+
+        let pair: FString = raw_fstring(&mut image, &to_utf16z("\u{1F600}"));
+        assert_eq!(pair.read_string(&image.mem).unwrap(), "\u{1F600}");
+
+        // Anything after the first terminator is ignored
+        let trailing: FString = raw_fstring(&mut image, &[u16::from(b'o'), u16::from(b'k'), 0, 0xD800]);
+        assert_eq!(trailing.read_string(&image.mem).unwrap(), "ok");
+    }
+
+    #[test]
+    fn units_leave_empty_strings_empty() {
+        assert_eq!(fstring_units(""), Vec::<u16>::new());
+        assert_eq!(fstring_units("ab"), vec![u16::from(b'a'), u16::from(b'b'), 0]);
+        assert_eq!(from_utf16z(&fstring_units("Caverns_P")).as_deref(), Some("Caverns_P"));
+    }
+
+    #[test]
+    fn temp_fstring_header_points_at_the_units() {
+        let empty: TempFString = TempFString::new("");
+        assert_eq!(empty.address(), 0);
+        assert_eq!(empty.header(), [0u8; TARRAY_SIZE]);
+        assert!(!empty.read_bytes(0, &mut [0u8; 2]));
+        assert!(!empty.read_bytes(0, &mut []));
+
+        let text: TempFString = TempFString::new("abc");
+        let header: [u8; TARRAY_SIZE] = text.header();
+        assert_eq!(u64::from_le_bytes(header[0x0..0x8].try_into().unwrap()) as usize, text.address());
+        assert_eq!(i32::from_le_bytes(header[0x8..0xC].try_into().unwrap()), 4);
+        assert_eq!(i32::from_le_bytes(header[0xC..0x10].try_into().unwrap()), 4);
+    }
+
+    #[test]
+    fn temp_fstring_reads_stay_inside_the_units() {
+        let text: TempFString = TempFString::new("abc");
+        let address: usize = text.address();
+        let mut buf = [0u8; 2];
+
+        assert!(text.read_bytes(address + 2, &mut buf));
+        assert_eq!(u16::from_le_bytes(buf), u16::from(b'b'));
+
+        let mut all = [0u8; 8];
+        assert!(text.read_bytes(address, &mut all));
+        assert_eq!(all, [b'a', 0, b'b', 0, b'c', 0, 0, 0]);
+
+        assert!(text.read_bytes(address + 8, &mut []));
+        assert!(!text.read_bytes(address + 7, &mut buf));
+        assert!(!text.read_bytes(address + 8, &mut [0u8; 1]));
+        assert!(!text.read_bytes(address - 1, &mut buf));
+        assert!(!text.read_bytes(usize::MAX, &mut buf));
+    }
+}
@@ -0,0 +1,325 @@
+use crate::error::{ReflectionError, ReflectionResult};
+use crate::fields::{CPF_OPTIONAL_PARM, CPF_OUT_PARM, CPF_PARM, CPF_RETURN_PARM, PropertyInfo, UPropertyRef, UStructRef, is_property_class};
+use crate::fstring::TempFString;
+use crate::memory::{MemoryReader, MemoryWriter};
+use crate::property::{PropertyValue, read_value, write_value};
+use crate::reflection::{TARRAY_SIZE, TArray, UObjectRef};
+
+/**
+ * The parameter layout of a UFunction, read from its CPF_Parm properties
+ */
+#[derive(Clone, Debug)]
+pub struct FunctionInfo {
+    pub function: UStructRef,
+    pub name: String,
+    /**
+     * Every parameter including out params and the return value, in declaration order
+     */
+    pub params: Vec<PropertyInfo>,
+    /**
+     * Size of the params buffer ProcessEvent expects, the UFunction's ParmsSize
+     */
+    pub parms_size: usize
+}
+
+/**
+ * What a call handed back, decoded out of the params buffer after ProcessEvent returned
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct CallResult {
+    pub return_value: Option<PropertyValue>,
+    pub out_params: Vec<(String, PropertyValue)>
+}
+
+impl FunctionInfo {
+    pub fn read(mem: &dyn MemoryReader, gnames: TArray, function: UStructRef) -> ReflectionResult<FunctionInfo> {
+        let mut params: Vec<PropertyInfo> = Vec::new();
+
+        // Only the function's own children, its SuperField is the function it overrides and that one declares the same params again
+        for field in function.children(mem)? {
+            if !is_property_class(&field.class_name(mem, gnames)?) {
+                continue;
+            }
+
+            let property: PropertyInfo = PropertyInfo::read(mem, gnames, UPropertyRef(field))?;
+
+            if property.has_flag(CPF_PARM) {
+                params.push(property);
+            }
+        }
+
+        let name: String = function.0.name(mem, gnames)?;
+        let parms_size: usize = function.parms_size(mem)?;
+        let params_end: usize = params.iter().map(|param| param.offset + param.size()).max().unwrap_or(0);
+
+        // A ParmsSize short of the last param means the layout is off, ProcessEvent would run past the buffer
+        if parms_size < params_end {
+            return Err(ReflectionError::SizeMismatch { name: name, expected: params_end, actual: parms_size });
+        }
+
+        return Ok(FunctionInfo { function: function, name: name, params: params, parms_size: parms_size });
+    }
+
+    /**
+     * Parameters the caller passes in, out params included as UnrealScript out params are passed by reference and may be read
+     */
+    pub fn inputs(&self) -> Vec<&PropertyInfo> {
+        return self.params.iter().filter(|param| !param.has_flag(CPF_RETURN_PARM)).collect();
+    }
+
+    pub fn out_params(&self) -> Vec<&PropertyInfo> {
+        return self.params.iter().filter(|param| param.has_flag(CPF_OUT_PARM) && !param.has_flag(CPF_RETURN_PARM)).collect();
+    }
+
+    pub fn return_param(&self) -> Option<&PropertyInfo> {
+        return self.params.iter().find(|param| param.has_flag(CPF_RETURN_PARM));
+    }
+
+    /**
+     * Builds a zeroed params buffer and writes args into it, one value per input in declaration order
+     * Trailing optional params may be left out, they stay zeroed which UnrealScript treats as not passed
+     * String params point at temporary FStrings owned by the buffer, so the buffer has to outlive the call
+     */
+    pub fn build_params<'a>(&self, mem: &'a dyn MemoryReader, gnames: TArray, args: &[PropertyValue]) -> ReflectionResult<ParamsBuffer<'a>> {
+        let inputs: Vec<&PropertyInfo> = self.inputs();
+        let required: usize = inputs.iter().filter(|param| !param.has_flag(CPF_OPTIONAL_PARM)).count();
+
+        if args.len() > inputs.len() || args.len() < required {
+            return Err(ReflectionError::ArgumentCount { function: self.name.clone(), expected: inputs.len(), given: args.len() });
+        }
+
+        let mut buffer: ParamsBuffer = ParamsBuffer::new(mem, self.parms_size);
+        let base: usize = buffer.address();
+
+        for (param, value) in inputs.iter().zip(args.iter()) {
+            match (param.class_name.as_str(), value) {
+                ("StrProperty", PropertyValue::Str(text)) if param.array_dim == 1 => buffer.push_string(base + param.offset, text),
+                _ => write_value(&mut buffer, gnames, None, param, base, value)?
+            }
+        }
+
+        return Ok(buffer);
+    }
+
+    /**
+     * Reads the return value and every out param back out of a buffer ProcessEvent has run on
+     */
+    pub fn decode_result(&self, gnames: TArray, buffer: &ParamsBuffer) -> ReflectionResult<CallResult> {
+        let base: usize = buffer.address();
+
+        let return_value: Option<PropertyValue> = match self.return_param() {
+            Some(param) => Some(read_value(buffer, gnames, param, base)?),
+            None => None
+        };
+
+        let mut out_params: Vec<(String, PropertyValue)> = Vec::new();
+
+        for param in self.out_params() {
+            out_params.push((param.name.clone(), read_value(buffer, gnames, param, base)?));
+        }
+
+        return Ok(CallResult { return_value: return_value, out_params: out_params });
+    }
+}
+
+/**
+ * A params buffer owned by the DLL, laid over the game's memory
+ * Reads and writes inside the buffer go to the buffer, reads anywhere else fall through to the game so the property writer can still follow UProperty metadata
+ * The buffer lives in the process itself, so address() can be handed to ProcessEvent as is
+ * It is backed by u64s so it is 8 byte aligned like the engine's own params, pointers and FStrings in it are read with aligned loads
+ */
+pub struct ParamsBuffer<'a> {
+    source: &'a dyn MemoryReader,
+    words: Vec<u64>,
+    size: usize,
+    strings: Vec<TempFString>
+}
+
+impl<'a> ParamsBuffer<'a> {
+    pub fn new(source: &'a dyn MemoryReader, size: usize) -> ParamsBuffer<'a> {
+        return ParamsBuffer { source: source, words: vec![0u64; size.div_ceil(8)], size: size, strings: Vec::new() };
+    }
+
+    pub fn address(&self) -> usize {
+        return self.words.as_ptr() as usize;
+    }
+
+    /**
+     * Pointer to hand to ProcessEvent, which writes out params and the return value back into the buffer
+     */
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        return self.words.as_mut_ptr() as *mut u8;
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        return unsafe { std::slice::from_raw_parts(self.words.as_ptr() as *const u8, self.size) };
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        return unsafe { std::slice::from_raw_parts_mut(self.words.as_mut_ptr() as *mut u8, self.size) };
+    }
+
+    /**
+     * Stores text as a temporary FString and writes its header at address, which has to lie inside the buffer
+     */
+    pub fn push_string(&mut self, address: usize, text: &str) {
+        let string: TempFString = TempFString::new(text);
+
+        if let Some(offset) = self.offset_of(address, TARRAY_SIZE) {
+            self.bytes_mut()[offset..offset + TARRAY_SIZE].copy_from_slice(&string.header());
+        }
+
+        self.strings.push(string);
+    }
+
+    /**
+     * Offset of address inside the buffer, if the whole range lies inside it
+     */
+    fn offset_of(&self, address: usize, len: usize) -> Option<usize> {
+        let offset: usize = address.checked_sub(self.address())?;
+
+        if offset.checked_add(len)? > self.size {
+            return None;
+        }
+
+        return Some(offset);
+    }
+}
+
+impl<'a> MemoryReader for ParamsBuffer<'a> {
+    fn read_bytes(&self, address: usize, buf: &mut [u8]) -> bool {
+        if let Some(offset) = self.offset_of(address, buf.len()) {
+            buf.copy_from_slice(&self.bytes()[offset..offset + buf.len()]);
+            return true;
+        }
+
+        if self.strings.iter().any(|string| string.read_bytes(address, buf)) {
+            return true;
+        }
+
+        return self.source.read_bytes(address, buf);
+    }
+}
+
+impl<'a> MemoryWriter for ParamsBuffer<'a> {
+    /**
+     * Only the buffer itself can be written, the game's memory stays untouched
+     */
+    fn write_bytes(&mut self, address: usize, data: &[u8]) -> bool {
+        let offset: usize = match self.offset_of(address, data.len()) {
+            Some(offset) => offset,
+            None => return false
+        };
+
+        self.bytes_mut()[offset..offset + data.len()].copy_from_slice(data);
+
+        return true;
+    }
+}
+
+
+/**
+ * Finds a function by name on the object's class, overrides in subclasses win over the functions they override
+ */
+pub fn find_function(mem: &dyn MemoryReader, gnames: TArray, object: UObjectRef, name: &str) -> ReflectionResult<FunctionInfo> {
+    for function in UStructRef(object.class(mem)?).functions(mem, gnames)? {
+        if function.0.name(mem, gnames)?.eq_ignore_ascii_case(name) {
+            return FunctionInfo::read(mem, gnames, function);
+        }
+    }
+
+    return Err(ReflectionError::FunctionNotFound { name: name.to_string() });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestImage;
+
+    /**
+     * Hud.ShowMessage(string Text, int Seconds, out int Shown, optional bool bFlash) returns float, ParmsSize 0x28
+     */
+    fn fixture(parms_size: usize) -> (TestImage, UObjectRef) {
+        let mut image: TestImage = TestImage::new();
+        let game: usize = image.add_package("Game");
+        let class: usize = image.add_class("Hud", game, 0, 0x100);
+        let function: usize = image.add_function(class, "ShowMessage", parms_size);
+
+        image.add_param(function, "StrProperty", "Text", 0x0, 0x10, 0);
+        image.add_param(function, "IntProperty", "Seconds", 0x10, 4, 0);
+        image.add_param(function, "IntProperty", "Shown", 0x14, 4, CPF_OUT_PARM);
+        image.add_param(function, "BoolProperty", "bFlash", 0x18, 4, CPF_OPTIONAL_PARM);
+        image.add_param(function, "FloatProperty", "ReturnValue", 0x1C, 4, CPF_OUT_PARM | CPF_RETURN_PARM);
+        image.add_property(function, "IntProperty", "Local", 0x20, 4, &[]);
+
+        let hud: usize = image.add_object_sized("Hud_0", game, class, 0x100);
+
+        return (image, UObjectRef(hud));
+    }
+
+    #[test]
+    fn reads_params_and_parms_size() {
+        let (image, hud) = fixture(0x28);
+        let function: FunctionInfo = find_function(&image.mem, image.gnames, hud, "showmessage").unwrap();
+
+        assert_eq!(function.name, "ShowMessage");
+        assert_eq!(function.parms_size, 0x28);
+        assert_eq!(function.params.iter().map(|param| param.name.as_str()).collect::<Vec<&str>>(), vec!["Text", "Seconds", "Shown", "bFlash", "ReturnValue"]);
+        assert_eq!(function.inputs().len(), 4);
+        assert_eq!(function.out_params().iter().map(|param| param.name.as_str()).collect::<Vec<&str>>(), vec!["Shown"]);
+        assert_eq!(function.return_param().unwrap().name, "ReturnValue");
+        assert!(matches!(find_function(&image.mem, image.gnames, hud, "Missing"), Err(ReflectionError::FunctionNotFound { .. })));
+    }
+
+    #[test]
+    fn parms_size_short_of_the_params_is_rejected() {
+        let (image, hud) = fixture(0x10);
+
+        assert!(matches!(find_function(&image.mem, image.gnames, hud, "ShowMessage"), Err(ReflectionError::SizeMismatch { expected: 0x20, actual: 0x10, .. })));
+    }
+
+    #[test]
+    fn builds_and_decodes_params() {
+        let (image, hud) = fixture(0x28);
+        let function: FunctionInfo = find_function(&image.mem, image.gnames, hud, "ShowMessage").unwrap();
+        let mut buffer: ParamsBuffer = function.build_params(&image.mem, image.gnames, &[PropertyValue::Str("Hello".to_string()), PropertyValue::Int(3), PropertyValue::Int(0)]).unwrap();
+        let base: usize = buffer.address();
+
+        assert_eq!(base % 8, 0);
+        assert_eq!(buffer.bytes().len(), 0x28);
+        assert_eq!(read_value(&buffer, image.gnames, &function.params[0], base).unwrap(), PropertyValue::Str("Hello".to_string()));
+        assert_eq!(buffer.read_i32(base + 0x10).unwrap(), 3);
+        // bFlash was left out and stays zeroed
+        assert_eq!(buffer.read_u32(base + 0x18).unwrap(), 0);
+
+        // What ProcessEvent would do
+        buffer.write_i32(base + 0x14, 7).unwrap();
+        buffer.write_f32(base + 0x1C, 0.5).unwrap();
+
+        let result: CallResult = function.decode_result(image.gnames, &buffer).unwrap();
+
+        assert_eq!(result.return_value, Some(PropertyValue::Float(0.5)));
+        assert_eq!(result.out_params, vec![("Shown".to_string(), PropertyValue::Int(7))]);
+    }
+
+    #[test]
+    fn argument_count_is_checked() {
+        let (image, hud) = fixture(0x28);
+        let function: FunctionInfo = find_function(&image.mem, image.gnames, hud, "ShowMessage").unwrap();
+
+        assert!(matches!(function.build_params(&image.mem, image.gnames, &[PropertyValue::Str("Hello".to_string())]), Err(ReflectionError::ArgumentCount { expected: 4, given: 1, .. })));
+        assert!(matches!(function.build_params(&image.mem, image.gnames, &[PropertyValue::Int(1), PropertyValue::Int(3), PropertyValue::Int(0)]), Err(ReflectionError::TypeMismatch { .. })));
+    }
+
+    #[test]
+    fn writes_outside_the_buffer_are_refused() {
+        let (image, _) = fixture(0x28);
+        let mut buffer: ParamsBuffer = ParamsBuffer::new(&image.mem, 0xC);
+        let base: usize = buffer.address();
+
+        assert!(buffer.write_bytes(base + 0x8, &[1, 2, 3, 4]));
+        assert!(!buffer.write_bytes(base + 0xA, &[1, 2, 3, 4]));
+        assert!(!buffer.write_bytes(image.gnames.address, &[0]));
+        assert_eq!(buffer.bytes(), &[0, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3, 4]);
+    }
+}
@@ -0,0 +1,1164 @@
+use minhook::MinHook;
+
+use std::{io::{stdout, stdin}, ptr::{self}, collections::HashMap, fs, path::{Path, PathBuf}, sync::OnceLock};
+
+use toy_arms::{internal::{self, module::Module}, derive::GameObject};
+
+use crate::diff::DumpDiff;
+use crate::dump::ReflectionDump;
+use crate::error::{ReflectionError, ReflectionResult};
+use crate::fields::{PropertyInfo, UStructRef, set_field_layout};
+use crate::flags::RF_PENDING_KILL_MASK;
+use crate::fstring::to_utf16z;
+use crate::function::{CallResult, FunctionInfo, ParamsBuffer, find_function};
+use crate::malloc::{EngineAllocator, FMallocSlots, GMalloc};
+use crate::memory::{MemoryReader, ProcessMemory};
+use crate::names::{FName, NameTable, read_wide_string};
+use crate::pe::PeImage;
+use crate::profile::{ExecutableIdentity, Offsets, OffsetProfile, load_profiles, select_profile};
+use crate::reflection::{TArray, UObjectRef, set_uobject_layout, uobject_layout};
+use crate::property::{PropertyValue, find_property, read_property, write_value};
+use crate::query::ObjectQuery;
+use crate::registry::ObjectRegistry;
+use crate::sdk::{SdkGenerator, SdkPackage, write_sdk};
+use crate::signature::{ModuleImage, Resolve};
+use crate::snapshot::GameSnapshot;
+
+internal::create_entrypoint!(main_thread);
+
+/**
+ * Offset profiles for every known Battleborn.exe build, read from the game directory like config.json unless profilesFile says otherwise
+ */
+const DEFAULT_PROFILES_FILE: &str = "profiles.json";
+
+/**
+ * Where reborn snapshot writes to when no path is given, next to the game
+ */
+const DEFAULT_SNAPSHOT_FILE: &str = "reflection.snapshot";
+
+static mut ORIG_PROCESSEVENT_ADDR: usize = 0;
+static mut ORIG_ENGINE_EXEC_ADDR: usize = 0;
+
+static mut ENGINE_ADDR: usize = 0;
+static mut FOUTPUTDEVICE: usize = 0;
+static mut MODULE_BASE_GLOBAL: usize = 0;
+static mut NAME_TABLE_GLOBAL: Option<NameTable> = None;
+static mut OBJECT_REGISTRY_GLOBAL: Option<ObjectRegistry> = None;
+
+/**
+ * Set once while hooking and only read afterwards
+ */
+static ON_START_ONLINE_GAME_COMPLETE_FNAME: OnceLock<FName> = OnceLock::new();
+static GMALLOC_GLOBAL: OnceLock<GMalloc> = OnceLock::new();
+static CONFIG_GLOBAL: OnceLock<Config> = OnceLock::new();
+static LEVEL_START_SETTINGS_GLOBAL: OnceLock<LevelStartSettings> = OnceLock::new();
+static GLOBAL_ARRAYS: OnceLock<GlobalArrays> = OnceLock::new();
+
+static PROCESS_MEMORY: ProcessMemory = ProcessMemory;
+
+/**
+ * GNames and GObjects, located together in main_thread
+ */
+struct GlobalArrays {
+    gnames: TArray,
+    gobjects: TArray
+}
+
+/**
+ * GNames and GObjects as located in main_thread, hooks and console commands can fire before that is done so every caller has to handle the error
+ */
+fn global_arrays() -> ReflectionResult<&'static GlobalArrays> {
+    return GLOBAL_ARRAYS.get().ok_or(ReflectionError::NotLocated { name: "GNames and GObjects".to_string() });
+}
+
+/**
+ * The name table and registry are refreshed on every level start, only ever from the game thread
+ * These go through raw pointers so no reference to the static mut itself is ever created
+ */
+unsafe fn name_table() -> Option<&'static NameTable> {
+    return (*ptr::addr_of!(NAME_TABLE_GLOBAL)).as_ref();
+}
+
+unsafe fn name_table_mut() -> Option<&'static mut NameTable> {
+    return (*ptr::addr_of_mut!(NAME_TABLE_GLOBAL)).as_mut();
+}
+
+unsafe fn object_registry() -> Option<&'static ObjectRegistry> {
+    return (*ptr::addr_of!(OBJECT_REGISTRY_GLOBAL)).as_ref();
+}
+
+unsafe fn object_registry_mut() -> Option<&'static mut ObjectRegistry> {
+    return (*ptr::addr_of_mut!(OBJECT_REGISTRY_GLOBAL)).as_mut();
+}
+
+/**
+ * Gets the currently instantiated camera UObject, a PoplarCamera or anything else deriving from Engine.Camera
+ */
+fn get_camera(registry: &ObjectRegistry) -> Option<UObjectRef>{
+    let entry = ObjectQuery::new().with_ancestor("Engine.Camera").with_outer("PersistentLevel").exclude_defaults().without_flags(RF_PENDING_KILL_MASK).first(registry)?;
+
+    println!("Camera {}", entry.path);
+
+    return Some(entry.object);
+}
+
+/**
+ * Gets the currently instantiated PlayerInput UObject, a PoplarPlayerInput or anything else deriving from Engine.PlayerInput
+ */
+fn get_input(registry: &ObjectRegistry) -> Option<UObjectRef>{
+    //[2a36c4a9850] [PoplarGame.PoplarPlayerInput] Slums_P.TheWorld.PersistentLevel.PoplarPlayerController.PoplarPlayerInput
+
+    let entry = ObjectQuery::new().with_ancestor("Engine.PlayerInput").with_outer("PoplarPlayerController*").with_outer("PersistentLevel").exclude_defaults().without_flags(RF_PENDING_KILL_MASK).first(registry)?;
+
+    println!("PlayerInput {}", entry.path);
+
+    return Some(entry.object);
+}
+
+/**
+ * Sets the mouse sensitivity of the currently active PlayerInput, must be called after each level load
+ */
+unsafe fn set_mouse_sensitivity(registry: &ObjectRegistry, x: f32, y: f32){
+    println!("Changing Sensitivity to X: {:?} Y: {:?}", x, y);
+
+    let input: UObjectRef = match get_input(registry) {
+        Some(input) => input,
+        None => {
+            println!("No PlayerInput to change the sensitivity of");
+            return;
+        }
+    };
+
+    if let Err(error) = call(input, "SetSensitivity", &[PropertyValue::Float(x), PropertyValue::Float(y)]) {
+        println!("Unable to change sensitivity: {}", error);
+    }
+}
+
+/**
+ * Sets the FOV of the currently active PlayerController, must be called after each level load
+ */
+unsafe fn set_fov(registry: &ObjectRegistry, fov: f32){
+    println!("Changing FOV to {:?}", fov);
+
+    let player_controller: UObjectRef = match get_player_controller_address(registry) {
+        Some(address) => UObjectRef(address),
+        None => {
+            println!("No PlayerController to change the FOV of");
+            return;
+        }
+    };
+
+    if let Err(error) = call(player_controller, "FOV", &[PropertyValue::Float(fov)]) {
+        println!("Unable to change FOV: {}", error);
+    }
+}
+
+/**
+ * Sets the subtitle state of the currently active PlayerController, must be called after each level load
+ */
+unsafe fn set_subtitle_state(registry: &ObjectRegistry, enabled: bool){
+    println!("Setting subtitles to {:?}", enabled);
+
+    let player_controller: UObjectRef = match get_player_controller_address(registry) {
+        Some(address) => UObjectRef(address),
+        None => {
+            println!("No PlayerController to set subtitles on");
+            return;
+        }
+    };
+
+    if let Err(error) = call(player_controller, "SetShowSubtitles", &[PropertyValue::Bool(enabled)]) {
+        println!("Unable to set subtitles: {}", error);
+    }
+}
+
+/**
+ * Calls a UFunction on an object by name, e.g. call(player_controller, "FOV", &[PropertyValue::Float(110.0)])
+ * The function is looked up through the object's class hierarchy and dispatched through the original ProcessEvent, the return value and out params are decoded from the params buffer afterwards
+ */
+unsafe fn call(object: UObjectRef, function_name: &str, args: &[PropertyValue]) -> ReflectionResult<CallResult> {
+    type ProcessEvent = unsafe extern "thiscall" fn(uobject: usize, ufunction: usize, params: usize) -> usize;
+
+    let process_event: ProcessEvent = unsafe { std::mem::transmute(ORIG_PROCESSEVENT_ADDR)};
+
+    let gnames: TArray = global_arrays()?.gnames;
+
+    let function: FunctionInfo = find_function(&PROCESS_MEMORY, gnames, object, function_name)?;
+
+    let mut params: ParamsBuffer = function.build_params(&PROCESS_MEMORY, gnames, args)?;
+
+    process_event(object.address(), function.function.0.address(), params.as_mut_ptr() as usize);
+
+    return function.decode_result(gnames, &params);
+}
+
+/**
+ * Looks up a UObject by its path name (Package.Outer.Name), optionally requiring it to be an instance of a class (or any subclass) given by path name
+ */
+fn get_uobject_by_name(registry: &ObjectRegistry, name: &str, class: Option<&str>) -> Option<UObjectRef>{
+    let entry = registry.get_by_path(name)?;
+
+    if let Some(class) = class {
+        if !registry.is_a(entry, registry.find_by_path(class)?) {
+            return None;
+        }
+    }
+
+    return Some(entry.object);
+}
+
+/**
+ * This is the function that is called whenever the original processevent is called
+ * This function intercepts the params of process_event, fires actions based on the state that the game is put into, then calls the original process_event function
+ */
+unsafe fn fake_process_event(uobject_address: usize, ufunction_address: usize, params: usize) -> usize{
+    type ProcessEvent = unsafe extern "thiscall" fn(uobject: usize, ufunction: usize, params: usize) -> usize;
+
+    let process_event: ProcessEvent = unsafe { std::mem::transmute(ORIG_PROCESSEVENT_ADDR)};
+
+    // Integer FName compare first, so the full name is only built for functions that can actually match
+    if let Some(fname) = ON_START_ONLINE_GAME_COMPLETE_FNAME.get() {
+        if FName::read(&PROCESS_MEMORY, ufunction_address + uobject_layout().name).ok() != Some(*fname) {
+            return process_event(uobject_address, ufunction_address, params);
+        }
+    }
+
+    let ufunction_name: ReflectionResult<String> = global_arrays().and_then(|arrays| UObjectRef(ufunction_address).path_name(&PROCESS_MEMORY, arrays.gnames));
+
+    match ufunction_name {
+        Ok(ufunction_name) => {
+            if ufunction_name == "Engine.GameInfo.OnStartOnlineGameComplete" {
+                on_level_start_callback();
+            }
+        }
+        Err(error) => println!("Skipping unreadable UFunction {:x}: {}", ufunction_address, error)
+    }
+
+    return process_event(uobject_address, ufunction_address, params);
+}
+
+/**
+ * The PlayerNameIdentifierDefinition every characterToLoad stands for
+ */
+const CHARACTER_NAME_IDS: [(&str, &str); 30] = [
+    ("WaterMonk", "GD_WaterMonk.NameId_WaterMonk"),
+    ("SunPriestess", "GD_SunPriestess.NameId_SunPriestess_Poplar"),
+    ("SoulCollector", "GD_SoulCollector.NameId_SoulCollector"),
+    ("PlagueBringer", "GD_PlagueBringer.NameId_PlagueBringer"),
+    ("RocketHawk", "GD_RocketHawk.NameId_RocketHawk"),
+    ("DwarvenWarrior", "GD_DwarvenWarrior.NameId_DwarvenWarrior"),
+    ("AssaultJump", "GD_AssaultJump.NameId_AssaultJump_Poplar"),
+    ("DarkAssassin", "GD_DarkAssassin.NameId_DarkAssassin"),
+    ("LeapingLuchador", "GD_LeapingLuchador.NameId_LeapingLuchador"),
+    ("Bombirdier", "GD_Bombirdier.NameId_Bombirdier"),
+    ("Blackguard", "GD_Blackguard.NameId_Blackguard"),
+    ("PapaShotgun", "GD_PapaShotgun.NameId_PapaShotgun"),
+    ("SpiritMech", "GD_SpiritMech.NameId_SpiritMech"),
+    ("IceGolem", "GD_IceGolem.NameId_IceGolem"),
+    ("SideKick", "GD_Sidekick.NameId_SideKick"),
+    ("TacticalBuilder", "GD_TacticalBuilder.NameId_TacticalBuilder"),
+    ("GentSniper", "gd_gentsniper.NameId_GentSniper"),
+    ("MutantFist", "GD_MutantFist.NameId_MutantFist"),
+    ("TribalHealer", "gd_tribalhealer.NameId_TribalHealer"),
+    ("MachineGunner", "gd_machinegunner.NameId_MachineGunner"),
+    ("ChaosMage", "GD_ChaosMage.NameId_ChaosMage"),
+    ("ModernSoldier", "gd_modernsoldier.NameId_ModernSoldier_Poplar"),
+    ("CornerSneaker", "GD_CornerSneaker.NameId_CornerSneaker"),
+    ("MageBlade", "GD_MageBlade.NameId_MageBlade_Poplar"),
+    ("DeathBlade", "gd_deathblade.NameId_DeathBlade"),
+    ("RogueCommander", "GD_RogueCommander.NameId_RogueCommander"),
+    ("BoyAndDjinn", "GD_BoyAndDjinn.NameId_BoyAndDjinn"),
+    ("DarkElf", "gd_darkelfranger.NameId_DarkElfRanger"),
+    ("PenguinMech", "GD_PenguinMech.NameId_PenguinMech"),
+    ("RogueSoldier", "GD_RogueSoldier.NameId_RogueSoldier"),
+];
+
+unsafe fn on_level_start_callback(){
+    let arrays: &GlobalArrays = match global_arrays() {
+        Ok(arrays) => arrays,
+        Err(error) => {
+            println!("Skipping level start: {}", error);
+            return;
+        }
+    };
+
+    if let Some(name_table) = name_table_mut() {
+        let _ = name_table.refresh(&PROCESS_MEMORY, arrays.gnames);
+    }
+
+    let registry: &mut ObjectRegistry = match object_registry_mut() {
+        Some(registry) => registry,
+        None => return
+    };
+
+    match registry.refresh(&PROCESS_MEMORY, arrays.gnames, arrays.gobjects) {
+        Ok(stats) => println!("Object registry refreshed, {} added {} removed {} renamed", stats.added, stats.removed, stats.renamed),
+        Err(error) => println!("Unable to refresh object registry: {}", error)
+    }
+
+    let registry: &ObjectRegistry = registry;
+
+    let settings: &LevelStartSettings = match LEVEL_START_SETTINGS_GLOBAL.get() {
+        Some(settings) => settings,
+        None => return
+    };
+
+    match (get_player_controller_address(registry), get_uobject_by_name(registry, settings.character_name_id, Some("PoplarGame.PoplarPlayerNameIdentifierDefinition"))) {
+        (Some(player_controller), Some(class_to_switch_to)) => {
+            if let Err(error) = call(UObjectRef(player_controller), "SwitchPoplarPlayerClass", &[PropertyValue::Object(Some(class_to_switch_to))]) {
+                println!("Unable to switch player class: {}", error);
+            }
+        }
+        (None, _) => println!("No PlayerController to switch the class of"),
+        (_, None) => println!("No {} to switch to", settings.character_name_id)
+    }
+
+    set_fov(registry, settings.fov);
+
+    set_mouse_sensitivity(registry, settings.mouse_sensitivity_x, settings.mouse_sensitivity_y);
+
+    set_subtitle_state(registry, settings.subtitles);
+}
+
+struct ConsoleCommandParams{
+    command: usize
+}
+
+#[derive(GameObject)]
+struct TcpListenParams{
+    returnval: usize
+}
+
+/**
+ * This is the function that is called whenever the original exec function of the GameEngine UObject is called
+ * This function intercepts the params of engine_exec, fires actions based on the state that the game is put into, then calls the original engine_exec function
+ * Every console command passes through here, the reborn commands are handled and swallowed, anything else goes on to the engine
+ */
+unsafe extern "thiscall" fn fake_engine_exec(game_engine_address: usize, command: usize, f_output_device: usize) -> i32{
+    type EngineCallCommand = unsafe extern "thiscall" fn(game_engine_address: usize, command: usize, f_output_device: usize) -> i32;
+
+    let engine_call_command: EngineCallCommand = unsafe{ std::mem::transmute(ORIG_ENGINE_EXEC_ADDR)};
+
+    ENGINE_ADDR = game_engine_address;
+    FOUTPUTDEVICE = f_output_device;
+
+    if let Ok(command_text) = read_wide_string(&PROCESS_MEMORY, command, 1024) {
+        if let Some(query_text) = command_text.strip_prefix("reborn find ") {
+            run_console_query(query_text);
+            return 1;
+        }
+
+        if let Some(class_path) = command_text.strip_prefix("reborn class ") {
+            run_console_class_dump(class_path.trim());
+            return 1;
+        }
+
+        if let Some(arguments) = command_text.strip_prefix("reborn get ") {
+            run_console_get_property(arguments);
+            return 1;
+        }
+
+        if let Some(arguments) = command_text.strip_prefix("reborn set ") {
+            run_console_set_property(arguments);
+            return 1;
+        }
+
+        if let Some(dir) = command_text.strip_prefix("reborn dump") {
+            let dir: &str = dir.trim();
+            export_dump(if dir.is_empty() { CONFIG_GLOBAL.get().and_then(|config| config.dumpDirectory.as_deref()).unwrap_or("dumps") } else { dir });
+            return 1;
+        }
+
+        if let Some(arguments) = command_text.strip_prefix("reborn diff ") {
+            run_console_diff(arguments);
+            return 1;
+        }
+
+        if let Some(dir) = command_text.strip_prefix("reborn sdk") {
+            run_console_sdk_dump(dir.trim());
+            return 1;
+        }
+
+        if let Some(path) = command_text.strip_prefix("reborn snapshot") {
+            run_console_snapshot(path.trim());
+            return 1;
+        }
+
+        if let Some(name) = command_text.strip_prefix("reborn module") {
+            run_console_module(name.trim());
+            return 1;
+        }
+    }
+
+    return engine_call_command(game_engine_address, command, f_output_device);
+}
+
+#[derive(GameObject)]
+struct ReturnToMenuParams{
+    reason: usize
+}
+
+struct TcpLinkListenParams{
+    returnval: usize
+}
+
+struct ClientTravelParams{
+    URL: usize,
+    travelType: u8,
+    bSeamless: u64,
+    mapPackageGUID: usize
+}
+
+struct SetFrontendStateParams{
+    state: u8
+}
+
+struct ServerSelectCharacterParams{
+    character: usize,
+    skin: usize,
+    taunt: usize
+}
+
+/**
+ * Gets the currently instantiated PlayerController UObject, a PoplarPlayerController or anything else deriving from Engine.PlayerController
+ */
+fn get_player_controller_address(registry: &ObjectRegistry) -> Option<usize>{
+    let entry = ObjectQuery::new().with_ancestor("Engine.PlayerController").with_outer("PersistentLevel").exclude_defaults().without_flags(RF_PENDING_KILL_MASK).first(registry)?;
+
+    println!("{}", entry.path);
+
+    return Some(entry.object.address());
+}
+
+/**
+ * Runs an object query typed into the game console and prints every match
+ */
+unsafe fn run_console_query(query_text: &str){
+    let query: ObjectQuery = match ObjectQuery::parse(query_text) {
+        Ok(query) => query,
+        Err(error) => {
+            println!("Bad query: {}", error);
+            return;
+        }
+    };
+
+    let registry: &ObjectRegistry = match object_registry() {
+        Some(registry) => registry,
+        None => {
+            println!("Object registry not built yet");
+            return;
+        }
+    };
+
+    let results = query.run(registry);
+
+    for entry in results.iter() {
+        println!("[{:x}] [{}] {} {}", entry.object.address(), registry.class_path(entry).unwrap_or(""), entry.path, entry.flags);
+    }
+
+    println!("{} objects matched", results.len());
+}
+
+/**
+ * Prints a property of an object typed into the game console, e.g. reborn get Slums_P.TheWorld.PersistentLevel.PoplarPlayerController_0 FOVAngle
+ */
+unsafe fn run_console_get_property(arguments: &str){
+    let (object_path, property_name) = match arguments.trim().split_once(' ') {
+        Some(split) => split,
+        None => {
+            println!("Usage: reborn get <object path> <property>");
+            return;
+        }
+    };
+
+    let object: UObjectRef = match object_registry().and_then(|registry| registry.find_by_path(object_path)) {
+        Some(object) => object,
+        None => {
+            println!("No object named {}", object_path);
+            return;
+        }
+    };
+
+    let gnames: TArray = match global_arrays() {
+        Ok(arrays) => arrays.gnames,
+        Err(error) => {
+            println!("Unable to read {}: {}", property_name.trim(), error);
+            return;
+        }
+    };
+
+    match read_property(&PROCESS_MEMORY, gnames, object, property_name.trim()) {
+        Ok(value) => println!("{}.{} = {}", object_path, property_name.trim(), value),
+        Err(error) => println!("Unable to read {}: {}", property_name.trim(), error)
+    }
+}
+
+/**
+ * Writes a property of an object typed into the game console, e.g. reborn set PoplarGame.Default__PoplarPlayerController FOVAngle 110
+ * Only simple properties can be set this way, the value is parsed according to the property's class
+ */
+unsafe fn run_console_set_property(arguments: &str){
+    let mut mem: ProcessMemory = ProcessMemory;
+
+    let gnames: TArray = match global_arrays() {
+        Ok(arrays) => arrays.gnames,
+        Err(error) => {
+            println!("Unable to set properties: {}", error);
+            return;
+        }
+    };
+
+    let mut parts = arguments.trim().splitn(3, ' ');
+
+    let (object_path, property_name, value_text) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(object_path), Some(property_name), Some(value_text)) => (object_path, property_name, value_text.trim()),
+        _ => {
+            println!("Usage: reborn set <object path> <property> <value>");
+            return;
+        }
+    };
+
+    let object: UObjectRef = match object_registry().and_then(|registry| registry.find_by_path(object_path)) {
+        Some(object) => object,
+        None => {
+            println!("No object named {}", object_path);
+            return;
+        }
+    };
+
+    let property: PropertyInfo = match find_property(&mem, gnames, object, property_name) {
+        Ok(property) => property,
+        Err(error) => {
+            println!("Unable to find {}: {}", property_name, error);
+            return;
+        }
+    };
+
+    let value: PropertyValue = match parse_console_value(&property, value_text) {
+        Some(value) => value,
+        None => {
+            println!("{} is not a valid {}", value_text, property.class_name);
+            return;
+        }
+    };
+
+    let allocator: Option<&dyn EngineAllocator> = GMALLOC_GLOBAL.get().map(|gmalloc| gmalloc as &dyn EngineAllocator);
+
+    match write_value(&mut mem, gnames, allocator, &property, object.address(), &value) {
+        Ok(()) => println!("{}.{} = {}", object_path, property_name, value),
+        Err(error) => println!("Unable to write {}: {}", property_name, error)
+    }
+}
+
+/**
+ * Parses console text into a value for a property, object and class values are path names or None
+ * Bytes bound to an enum take either the number or the value name, e.g. TRAVEL_Absolute
+ */
+unsafe fn parse_console_value(property: &PropertyInfo, text: &str) -> Option<PropertyValue> {
+    let find_object = |text: &str| -> Option<Option<UObjectRef>> {
+        if text.eq_ignore_ascii_case("None") {
+            return Some(None);
+        }
+        return object_registry()?.find_by_path(text).map(Some);
+    };
+
+    return match property.class_name.as_str() {
+        "IntProperty" => text.parse::<i32>().ok().map(PropertyValue::Int),
+        "FloatProperty" => text.parse::<f32>().ok().map(PropertyValue::Float),
+        "BoolProperty" => match text.to_lowercase().as_str() {
+            "true" | "1" => Some(PropertyValue::Bool(true)),
+            "false" | "0" => Some(PropertyValue::Bool(false)),
+            _ => None
+        },
+        "ByteProperty" => match text.parse::<u8>() {
+            Ok(value) => Some(PropertyValue::Byte(value)),
+            Err(_) => Some(PropertyValue::Enum(0, text.to_string()))
+        },
+        "NameProperty" => name_table()?.find_fname(text).map(|fname| PropertyValue::Name(fname, text.to_string())),
+        "StrProperty" => Some(PropertyValue::Str(text.trim_matches('"').to_string())),
+        "ObjectProperty" | "ComponentProperty" => find_object(text).map(PropertyValue::Object),
+        "ClassProperty" => find_object(text).map(PropertyValue::Class),
+        _ => None
+    };
+}
+
+/**
+ * Exports names, objects, classes, properties and functions as dump.json plus one CSV per table
+ */
+unsafe fn export_dump(dir: &str){
+    let (name_table, registry) = match (name_table(), object_registry()) {
+        (Some(name_table), Some(registry)) => (name_table, registry),
+        _ => {
+            println!("Name table and object registry have to be built before dumping");
+            return;
+        }
+    };
+
+    let gnames: TArray = match global_arrays() {
+        Ok(arrays) => arrays.gnames,
+        Err(error) => {
+            println!("Unable to dump: {}", error);
+            return;
+        }
+    };
+
+    println!("Dumping to {}...", dir);
+
+    let dump: ReflectionDump = ReflectionDump::collect(&PROCESS_MEMORY, gnames, name_table, registry);
+
+    match dump.write(std::path::Path::new(dir)) {
+        Ok(()) => println!("Dumped {} names, {} objects, {} classes, {} properties and {} functions", dump.names.len(), dump.objects.len(), dump.classes.len(), dump.properties.len(), dump.functions.len()),
+        Err(error) => println!("Unable to write dump to {}: {}", dir, error)
+    }
+}
+
+/**
+ * Compares two dump directories typed into the game console, e.g. reborn diff dumps/Slums_P dumps/Portal_P
+ * The full report is written next to the newer dump as diff.txt and diff.json
+ */
+fn run_console_diff(arguments: &str){
+    let (old_dir, new_dir) = match arguments.trim().split_once(' ') {
+        Some((old_dir, new_dir)) => (std::path::Path::new(old_dir.trim()), std::path::Path::new(new_dir.trim())),
+        None => {
+            println!("Usage: reborn diff <old dump directory> <new dump directory>");
+            return;
+        }
+    };
+
+    let (old_dump, new_dump) = match (ReflectionDump::load(old_dir), ReflectionDump::load(new_dir)) {
+        (Ok(old_dump), Ok(new_dump)) => (old_dump, new_dump),
+        (Err(error), _) | (_, Err(error)) => {
+            println!("Unable to load dumps: {}", error);
+            return;
+        }
+    };
+
+    let diff: DumpDiff = DumpDiff::compare(&old_dump, &new_dump);
+
+    println!("{} objects added, {} removed, {} changed, {} classes added, {} removed, {} functions added, {} removed", diff.added_objects.len(), diff.removed_objects.len(), diff.changed_objects.len(), diff.added_classes.len(), diff.removed_classes.len(), diff.added_functions.len(), diff.removed_functions.len());
+
+    let written = fs::write(new_dir.join("diff.txt"), diff.to_string()).and_then(|_| fs::write(new_dir.join("diff.json"), serde_json::to_string_pretty(&diff)?));
+
+    if let Err(error) = written {
+        println!("Unable to write diff to {}: {}", new_dir.display(), error);
+    }
+}
+
+/**
+ * Generates the Rust SDK from the live game typed into the game console, e.g. reborn sdk C:\\sdk, the directory defaults to sdk next to the game
+ */
+unsafe fn run_console_sdk_dump(dir: &str){
+    let registry: &ObjectRegistry = match object_registry() {
+        Some(registry) => registry,
+        None => return
+    };
+
+    let gnames: TArray = match global_arrays() {
+        Ok(arrays) => arrays.gnames,
+        Err(error) => {
+            println!("Unable to generate SDK: {}", error);
+            return;
+        }
+    };
+
+    let dir: &str = if dir.is_empty() { "sdk" } else { dir };
+
+    let packages: Vec<SdkPackage> = SdkGenerator::new(&PROCESS_MEMORY, gnames, registry).generate();
+
+    match write_sdk(&packages, std::path::Path::new(dir)) {
+        Ok(()) => println!("Wrote {} SDK packages to {}", packages.len(), dir),
+        Err(error) => println!("Unable to write SDK to {}: {}", dir, error)
+    }
+}
+
+/**
+ * Saves everything the SDK generator reads to a file typed into the game console, e.g. reborn snapshot C:\\battleborn.snapshot
+ * sdkgen turns the file into the SDK later on, without the game running
+ */
+unsafe fn run_console_snapshot(path: &str){
+    let path: &str = if path.is_empty() { DEFAULT_SNAPSHOT_FILE } else { path };
+
+    let snapshot: GameSnapshot = match global_arrays().and_then(|arrays| GameSnapshot::capture(&PROCESS_MEMORY, arrays.gnames, arrays.gobjects)) {
+        Ok(snapshot) => snapshot,
+        Err(error) => {
+            println!("Unable to capture snapshot: {}", error);
+            return;
+        }
+    };
+
+    match snapshot.save(Path::new(path)) {
+        Ok(()) => println!("Wrote {} regions to {}", snapshot.memory.regions().len(), path),
+        Err(error) => println!("Unable to write snapshot to {}: {}", path, error)
+    }
+}
+
+/**
+ * Prints the headers, sections, exports and imports of a loaded module typed into the game console, e.g. reborn module ReBorn.dll
+ * Defaults to Battleborn.exe, whose timestamp is what profiles.json tells builds apart by
+ */
+fn run_console_module(name: &str){
+    let name: &str = if name.is_empty() { "Battleborn.exe" } else { name };
+
+    let module_image: ModuleImage = match Module::from_name(name).map(|module| ModuleImage::read(&PROCESS_MEMORY, module.base_address)) {
+        Some(Ok(module_image)) => module_image,
+        Some(Err(error)) => {
+            println!("Unable to read {}: {}", name, error);
+            return;
+        }
+        None => {
+            println!("No module named {}", name);
+            return;
+        }
+    };
+
+    let pe: PeImage = match module_image.pe() {
+        Ok(pe) => pe,
+        Err(error) => {
+            println!("Unable to parse {}: {}", name, error);
+            return;
+        }
+    };
+
+    println!("{} at {:x} timestamp {:08x} size {:x}", name, module_image.base, pe.timestamp, pe.size_of_image);
+
+    for section in pe.sections.iter() {
+        println!("[Section] {} [{:x}] size {:x} flags {:x}", section.name, section.virtual_address, section.virtual_size, section.characteristics);
+    }
+
+    match pe.exports() {
+        Ok(exports) => {
+            for export in exports.iter() {
+                match &export.forwarder {
+                    Some(forwarder) => println!("[Export] #{} {} -> {}", export.ordinal, export.name.as_deref().unwrap_or(""), forwarder),
+                    None => println!("[Export] #{} {} [{:x}]", export.ordinal, export.name.as_deref().unwrap_or(""), export.rva)
+                }
+            }
+        }
+        Err(error) => println!("Unable to read exports: {}", error)
+    }
+
+    match pe.imports() {
+        Ok(imports) => {
+            for import in imports.iter() {
+                println!("[Import] {} {} functions", import.module, import.functions.len());
+            }
+        }
+        Err(error) => println!("Unable to read imports: {}", error)
+    }
+}
+
+/**
+ * Prints every property and function of a class (or any other UStruct) typed into the game console, e.g. reborn class PoplarGame.PoplarPlayerController
+ */
+unsafe fn run_console_class_dump(class_path: &str){
+    let mem: &dyn MemoryReader = &PROCESS_MEMORY;
+
+    let gnames: TArray = match global_arrays() {
+        Ok(arrays) => arrays.gnames,
+        Err(error) => {
+            println!("Unable to dump {}: {}", class_path, error);
+            return;
+        }
+    };
+
+    let class: UObjectRef = match object_registry().and_then(|registry| registry.find_by_path(class_path)) {
+        Some(class) => class,
+        None => {
+            println!("No object named {}", class_path);
+            return;
+        }
+    };
+
+    let ustruct: UStructRef = UStructRef(class);
+
+    match ustruct.property_size(mem) {
+        Ok(size) => println!("{} size {:x}", class_path, size),
+        Err(error) => println!("Unable to read {}: {}", class_path, error)
+    }
+
+    match ustruct.properties(mem, gnames) {
+        Ok(properties) => {
+            for property in properties.iter() {
+                println!("[{:x}] [{}] {} size {:x} flags {:x}", property.offset, property.class_name, property.name, property.size(), property.flags);
+            }
+        }
+        Err(error) => println!("Unable to read properties: {}", error)
+    }
+
+    match ustruct.functions(mem, gnames) {
+        Ok(functions) => {
+            for function in functions.iter() {
+                println!("[Function] {}", function.0.path_name(mem, gnames).unwrap_or_default());
+            }
+        }
+        Err(error) => println!("Unable to read functions: {}", error)
+    }
+}
+
+#[derive(GameObject)]
+struct CreateNamedNetDriverParams{
+    name: usize
+}
+
+#[derive(serde::Deserialize, Clone)]
+struct Config{
+    FOV: String,
+    MouseSensitivityX: String,
+    MouseSensitivityY: String,
+    subtitles: String,
+    mapToLoad: String,
+    characterToLoad: String,
+    /**
+     * Where names, objects, classes, properties and functions are exported to on injection, nothing is exported if unset
+     */
+    #[serde(default)]
+    dumpDirectory: Option<String>,
+    /**
+     * Offset of the GMalloc global from the module base in hex, wins over gmallocSignature
+     */
+    #[serde(default)]
+    gmallocOffset: Option<String>,
+    /**
+     * Signature of an instruction loading GMalloc RIP-relative, the first ?? ?? ?? ?? is the displacement, e.g. 48 8B 0D ?? ?? ?? ?? 48 8B 01
+     */
+    #[serde(default)]
+    gmallocSignature: Option<String>,
+    /**
+     * Signatures for GNames, GObjects, ProcessEvent and EngineProcessCommand, matched against the module's code
+     * GNames and GObjects are followed through their first ?? ?? ?? ?? like gmallocSignature, functions are matched from their first byte
+     */
+    #[serde(default)]
+    signatures: HashMap<String, String>,
+    /**
+     * Bytes left of the instruction after its ?? ?? ?? ?? in hex, keyed like signatures with GMalloc for gmallocSignature
+     * Only needed when the instruction ends in an immediate, e.g. "0x1" for cmp byte ptr [rip+disp32], imm8
+     */
+    #[serde(default)]
+    signatureTrailingBytes: HashMap<String, String>,
+    /**
+     * Path of the offset profiles, profiles.json when unset
+     */
+    #[serde(default)]
+    profilesFile: Option<String>,
+    /**
+     * Name of the profile to use whatever build is running, normally the profile is picked by the executable's timestamp or hash
+     * Profiles with neither are only ever used through this
+     */
+    #[serde(default)]
+    profile: Option<String>
+}
+
+/**
+ * The config values applied on every level start, parsed once while injecting so the hook never has to
+ */
+struct LevelStartSettings {
+    fov: f32,
+    mouse_sensitivity_x: f32,
+    mouse_sensitivity_y: f32,
+    subtitles: bool,
+    /**
+     * Path of the PlayerNameIdentifierDefinition characterToLoad stands for
+     */
+    character_name_id: &'static str
+}
+
+impl Config {
+    /**
+     * Parses the values on_level_start_callback applies, the error names the first one that does not parse
+     */
+    fn level_start_settings(&self) -> Result<LevelStartSettings, String> {
+        let parse_f32 = |key: &str, text: &str| -> Result<f32, String> {
+            return str::parse::<f32>(text).map_err(|_| format!("{} {} is not a number", key, text));
+        };
+
+        let subtitles: bool = str::parse::<bool>(&self.subtitles).map_err(|_| format!("subtitles {} is not true or false", self.subtitles))?;
+
+        let character_name_id: &'static str = match CHARACTER_NAME_IDS.iter().find(|(character, _)| *character == self.characterToLoad) {
+            Some((_, name_id)) => name_id,
+            None => return Err(format!("characterToLoad {} is not a known character", self.characterToLoad))
+        };
+
+        return Ok(LevelStartSettings {
+            fov: parse_f32("FOV", &self.FOV)?,
+            mouse_sensitivity_x: parse_f32("MouseSensitivityX", &self.MouseSensitivityX)?,
+            mouse_sensitivity_y: parse_f32("MouseSensitivityY", &self.MouseSensitivityY)?,
+            subtitles: subtitles,
+            character_name_id: character_name_id
+        });
+    }
+}
+
+/**
+ * Finds the GMalloc global from the configured offset, or by scanning the module for the configured signature
+ */
+fn locate_gmalloc(config: &Config, module_image: Option<&ModuleImage>, module_base_address: usize, slots: FMallocSlots) -> Option<GMalloc> {
+    if let Some(offset) = &config.gmallocOffset {
+        return match usize::from_str_radix(offset.trim_start_matches("0x"), 16) {
+            Ok(offset) => Some(GMalloc::new(module_base_address + offset, slots)),
+            Err(_) => {
+                println!("gmallocOffset {} is not a hex offset", offset);
+                None
+            }
+        };
+    }
+
+    return match module_image?.scan(config.gmallocSignature.as_deref()?, rip_relative(config, "GMalloc")) {
+        Ok(address) => Some(GMalloc::new(address, slots)),
+        Err(error) => {
+            println!("gmallocSignature: {}", error);
+            None
+        }
+    };
+}
+
+/**
+ * Picks the offset profile for the running executable, None (after saying why) when there is none or it does not parse
+ */
+fn load_offsets(config: &Config, module_base_address: usize) -> Option<Offsets> {
+    let profiles_file: &str = config.profilesFile.as_deref().unwrap_or(DEFAULT_PROFILES_FILE);
+
+    let profiles: Vec<OffsetProfile> = match load_profiles(Path::new(profiles_file)) {
+        Ok(profiles) => profiles,
+        Err(error) => {
+            println!("{}: {}", profiles_file, error);
+            return None;
+        }
+    };
+
+    let executable: PathBuf = std::env::current_exe().unwrap_or_default();
+
+    let identity: ExecutableIdentity = match ExecutableIdentity::read(&PROCESS_MEMORY, module_base_address, &executable) {
+        Ok(identity) => identity,
+        Err(error) => {
+            println!("Unable to identify {}: {}", executable.display(), error);
+            return None;
+        }
+    };
+
+    let profile: &OffsetProfile = match select_profile(&profiles, &identity, config.profile.as_deref()) {
+        Ok(profile) => profile,
+        Err(error) => {
+            println!("{}: {}", profiles_file, error);
+            return None;
+        }
+    };
+
+    if profile.is_unidentified() {
+        println!("Profile {} has no timestamp or hash and is only used because config.json names it. Add timestamp {:08x} to it once this build is known to work", profile.name, identity.timestamp);
+    }
+
+    return match profile.offsets() {
+        Ok(offsets) => {
+            println!("Using offset profile {}", profile.name);
+            Some(offsets)
+        }
+        Err(error) => {
+            println!("{}: {}", profiles_file, error);
+            None
+        }
+    };
+}
+
+/**
+ * How to follow the signature configured under name to the global it loads, an unparsable trailing byte count counts as none
+ */
+fn rip_relative(config: &Config, name: &str) -> Resolve {
+    let trailing: usize = match config.signatureTrailingBytes.get(name) {
+        Some(trailing) => usize::from_str_radix(trailing.trim_start_matches("0x"), 16).unwrap_or_else(|_| {
+            println!("{} trailing bytes {} is not a hex number", name, trailing);
+            0
+        }),
+        None => 0
+    };
+
+    return Resolve::RipRelative { trailing: trailing };
+}
+
+/**
+ * Address of a global or function, found by the signature configured under name when there is one
+ * Falls back to the offset from the build's profile when there is no signature or it does not match
+ */
+fn locate_address(config: &Config, module_image: Option<&ModuleImage>, module_base_address: usize, name: &str, resolve: Resolve, fallback_offset: usize) -> usize {
+    if let (Some(signature), Some(module_image)) = (config.signatures.get(name), module_image) {
+        match module_image.scan(signature, resolve) {
+            Ok(address) => {
+                println!("{} found at {:x}", name, address);
+                return address;
+            }
+            Err(error) => println!("{} signature: {}, using the profile offset", name, error)
+        }
+    }
+
+    return module_base_address + fallback_offset;
+}
+
+fn main_thread() {
+    let map_ipc_dict: HashMap<&str, Vec<u16>> = [
+        "PvE_Prologue_P",
+        "Caverns_P",
+        "Portal_P",
+        "Captains_P",
+        "Evacuation_P",
+        "Ruins_P",
+        "Observatory_p",
+        "Refinery_P",
+        "Cathedral_P",
+        "Slums_P",
+        "Toby_Raid_P",
+        "CullingFacility_P",
+        "TallTales_P",
+        "Heart_Ekkunar_P"
+    ].into_iter().map(|map| (map, to_utf16z(&format!("open {}", map)))).collect();
+
+    println!("ReBorn Injected!");
+
+    println!("Reading config.json...");
+
+    let config: Config = match fs::read_to_string("config.json").map_err(|error| error.to_string()).and_then(|text| serde_json::from_str(&text).map_err(|error| error.to_string())) {
+        Ok(config) => config,
+        Err(error) => {
+            println!("Unable to read config.json, not hooking: {}", error);
+            return;
+        }
+    };
+
+    let level_start_settings: LevelStartSettings = match config.level_start_settings() {
+        Ok(settings) => settings,
+        Err(error) => {
+            println!("config.json: {}, not hooking", error);
+            return;
+        }
+    };
+
+    let open_map_command: Vec<u16> = match map_ipc_dict.get(config.mapToLoad.as_str()) {
+        Some(command) => command.clone(),
+        None => {
+            println!("config.json: mapToLoad {} is not a known map, not hooking", config.mapToLoad);
+            return;
+        }
+    };
+
+    println!("Waiting for module to become valid...");
+
+    loop{
+        if Module::from_name("Battleborn.exe").is_some(){
+            break;
+        }
+    }
+    println!("Module valid! Continuing...");
+    let module: Module = Module::from_name("Battleborn.exe").unwrap();
+
+    let module_base_address: usize = module.base_address;
+
+    println!("Module base address: {:x}", module_base_address);
+
+    println!("Selecting offset profile...");
+
+    let offsets: Offsets = match load_offsets(&config, module_base_address) {
+        Some(offsets) => offsets,
+        None => {
+            println!("No usable offset profile for this build, not hooking. Add one for the timestamp or hash above, or name one with profile in config.json");
+            return;
+        }
+    };
+
+    set_uobject_layout(offsets.layout);
+    set_field_layout(offsets.fields);
+
+    unsafe{
+        let _ = CONFIG_GLOBAL.set(config.clone());
+        let _ = LEVEL_START_SETTINGS_GLOBAL.set(level_start_settings);
+        MODULE_BASE_GLOBAL = module_base_address;
+
+        let module_image: Option<ModuleImage> = match ModuleImage::read(&PROCESS_MEMORY, module_base_address) {
+            Ok(module_image) => Some(module_image),
+            Err(error) => {
+                println!("Unable to read module image, using profile offsets: {}", error);
+                None
+            }
+        };
+
+        let gnames: TArray = TArray::new(locate_address(&config, module_image.as_ref(), module_base_address, "GNames", rip_relative(&config, "GNames"), offsets.gnames));
+        let gobjects: TArray = TArray::new(locate_address(&config, module_image.as_ref(), module_base_address, "GObjects", rip_relative(&config, "GObjects"), offsets.gobjects));
+
+        let _ = GLOBAL_ARRAYS.set(GlobalArrays { gnames: gnames, gobjects: gobjects });
+
+        println!("Building name table...");
+
+        match NameTable::build(&PROCESS_MEMORY, gnames) {
+            Ok(name_table) => {
+                println!("Name table built with {} names", name_table.len());
+                if let Some(fname) = name_table.find_fname("OnStartOnlineGameComplete") {
+                    let _ = ON_START_ONLINE_GAME_COMPLETE_FNAME.set(fname);
+                }
+                NAME_TABLE_GLOBAL = Some(name_table);
+            }
+            Err(error) => println!("Unable to build name table: {}", error)
+        }
+
+        println!("Building object registry...");
+
+        match ObjectRegistry::build(&PROCESS_MEMORY, gnames, gobjects) {
+            Ok(registry) => {
+                println!("Object registry built with {} objects", registry.len());
+                OBJECT_REGISTRY_GLOBAL = Some(registry);
+            }
+            Err(error) => println!("Unable to build object registry: {}", error)
+        }
+
+        println!("Locating GMalloc...");
+
+        match locate_gmalloc(&config, module_image.as_ref(), module_base_address, offsets.fmalloc) {
+            Some(gmalloc) => {
+                println!("GMalloc at {:x}", gmalloc.address);
+                let _ = GMALLOC_GLOBAL.set(gmalloc);
+            }
+            None => println!("GMalloc not configured, strings and arrays can only be written in place")
+        }
+
+        if let Some(dump_directory) = &config.dumpDirectory {
+            export_dump(dump_directory);
+        }
+
+        println!("Creating ProcessEvent reference...");
+
+        type ProcessEvent = unsafe extern "thiscall" fn(uobject: usize, ufunction: usize, params: usize);
+
+        let process_event: ProcessEvent = std::mem::transmute(locate_address(&config, module_image.as_ref(), module_base_address, "ProcessEvent", Resolve::Match, offsets.process_event));
+
+        println!("Creating ProcessEvent hook...");
+
+        ORIG_PROCESSEVENT_ADDR = MinHook::create_hook(process_event as _, fake_process_event as _).unwrap() as usize;
+
+        println!("Creating EngineCallCommand reference...");
+
+        type EngineCallCommand = unsafe extern "thiscall" fn(UGameEngine: usize, command: usize, foutputdevice: usize) -> i32;
+
+        let engine_call_command: EngineCallCommand = std::mem::transmute(locate_address(&config, module_image.as_ref(), module_base_address, "EngineProcessCommand", Resolve::Match, offsets.engine_process_command));
+
+        println!("Creating EngineCallCommand hook...");
+
+        ORIG_ENGINE_EXEC_ADDR = MinHook::create_hook(engine_call_command as _, fake_engine_exec as _).unwrap() as usize;
+
+        println!("Enabling all hooks...");
+
+        MinHook::enable_all_hooks().unwrap();
+
+        let _stdin = stdin();
+        let _stdout = stdout();
+
+        println!("Loading map...");
+
+        match gobjects.get(&PROCESS_MEMORY, 0) {
+            Ok(first_object) => {
+                engine_call_command(first_object + offsets.engine_delta, open_map_command.as_ptr() as usize, 0);
+            }
+            Err(error) => println!("Unable to load {}: {}", config.mapToLoad, error)
+        }
+
+        // Keeps the injected thread alive without spinning, nothing ever unparks it
+        loop{
+            std::thread::park();
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,158 @@
+use crate::error::{ReflectionError, ReflectionResult};
+use crate::memory::{MemoryReader, ProcessMemory};
+
+/**
+ * Lets FMalloc pick the alignment, 16 bytes on x64
+ */
+pub const DEFAULT_ALIGNMENT: u32 = 0;
+
+/**
+ * UE3 declares class FMalloc : public FExec, so FExec::Exec takes vtable slot 0 and FMalloc's own virtuals follow in declaration order: Malloc, Realloc, Free
+ */
+const FMALLOC_MALLOC_SLOT: usize = 1;
+const FMALLOC_REALLOC_SLOT: usize = 2;
+const FMALLOC_FREE_SLOT: usize = 3;
+
+/**
+ * Where Malloc, Realloc and Free sit in the FMalloc vtable, in slots rather than bytes
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FMallocSlots {
+    pub malloc: usize,
+    pub realloc: usize,
+    pub free: usize
+}
+
+impl Default for FMallocSlots {
+    fn default() -> FMallocSlots {
+        return DEFAULT_FMALLOC_SLOTS;
+    }
+}
+
+const DEFAULT_FMALLOC_SLOTS: FMallocSlots = FMallocSlots {
+    malloc: FMALLOC_MALLOC_SLOT,
+    realloc: FMALLOC_REALLOC_SLOT,
+    free: FMALLOC_FREE_SLOT
+};
+
+/**
+ * The allocator the engine frees game owned memory with
+ * Anything stored into a TArray or FString the game keeps (and eventually frees) has to come from here, memory from the Rust allocator would corrupt the engine's heap
+ */
+pub trait EngineAllocator {
+    fn malloc(&self, size: usize, alignment: u32) -> ReflectionResult<usize>;
+
+    /**
+     * Grows or shrinks an allocation made by malloc, the contents up to the smaller size are kept
+     * On success original belongs to the allocator, on failure it is left as it was
+     */
+    fn realloc(&self, original: usize, size: usize, alignment: u32) -> ReflectionResult<usize>;
+
+    fn free(&self, address: usize);
+}
+
+/**
+ * The engine's global FMalloc, address is the GMalloc global itself (a pointer to the FMalloc instance)
+ * The instance is read on every call since GMalloc is only set up once the engine has initialized
+ * Calls go straight into the game, so this only works from inside the injected DLL and only through EngineAllocator
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct GMalloc {
+    pub address: usize,
+    pub slots: FMallocSlots
+}
+
+/**
+ * FMalloc methods are member functions, thiscall on 32 bit x86
+ * x64 has a single calling convention with this passed as the first argument, which is what "C" is there
+ */
+#[cfg(target_arch = "x86")]
+type FMallocMalloc = unsafe extern "thiscall" fn(this: usize, count: u32, alignment: u32) -> usize;
+#[cfg(target_arch = "x86")]
+type FMallocRealloc = unsafe extern "thiscall" fn(this: usize, original: usize, count: u32, alignment: u32) -> usize;
+#[cfg(target_arch = "x86")]
+type FMallocFree = unsafe extern "thiscall" fn(this: usize, original: usize);
+
+#[cfg(not(target_arch = "x86"))]
+type FMallocMalloc = unsafe extern "C" fn(this: usize, count: u32, alignment: u32) -> usize;
+#[cfg(not(target_arch = "x86"))]
+type FMallocRealloc = unsafe extern "C" fn(this: usize, original: usize, count: u32, alignment: u32) -> usize;
+#[cfg(not(target_arch = "x86"))]
+type FMallocFree = unsafe extern "C" fn(this: usize, original: usize);
+
+impl GMalloc {
+    pub fn new(address: usize, slots: FMallocSlots) -> GMalloc {
+        return GMalloc { address: address, slots: slots };
+    }
+
+    /**
+     * The FMalloc instance and the function in its vtable slot
+     */
+    fn virtual_function(&self, slot: usize) -> ReflectionResult<(usize, usize)> {
+        let instance: usize = ProcessMemory.read_pointer(self.address)?;
+        let vtable: usize = ProcessMemory.read_pointer(instance)?;
+
+        return Ok((instance, ProcessMemory.read_pointer(vtable + (slot * 0x8))?));
+    }
+
+    fn call_malloc(&self, size: usize, alignment: u32) -> ReflectionResult<usize> {
+        let count: u32 = u32::try_from(size).map_err(|_| ReflectionError::AllocationFailed { size: size })?;
+        let (instance, function) = self.virtual_function(self.slots.malloc)?;
+        let malloc: FMallocMalloc = unsafe { std::mem::transmute(function) };
+
+        let address: usize = unsafe { malloc(instance, count, alignment) };
+
+        if address == 0 {
+            return Err(ReflectionError::AllocationFailed { size: size });
+        }
+
+        return Ok(address);
+    }
+
+    /**
+     * Realloc returns null both when it fails and when it frees (size 0), only the first is an error
+     */
+    fn call_realloc(&self, original: usize, size: usize, alignment: u32) -> ReflectionResult<usize> {
+        let count: u32 = u32::try_from(size).map_err(|_| ReflectionError::AllocationFailed { size: size })?;
+        let (instance, function) = self.virtual_function(self.slots.realloc)?;
+        let realloc: FMallocRealloc = unsafe { std::mem::transmute(function) };
+
+        let address: usize = unsafe { realloc(instance, original, count, alignment) };
+
+        if address == 0 && size != 0 {
+            return Err(ReflectionError::AllocationFailed { size: size });
+        }
+
+        return Ok(address);
+    }
+
+    fn call_free(&self, original: usize) -> ReflectionResult<()> {
+        if original == 0 {
+            return Ok(());
+        }
+
+        let (instance, function) = self.virtual_function(self.slots.free)?;
+        let free: FMallocFree = unsafe { std::mem::transmute(function) };
+
+        unsafe { free(instance, original) };
+
+        return Ok(());
+    }
+}
+
+impl EngineAllocator for GMalloc {
+    fn malloc(&self, size: usize, alignment: u32) -> ReflectionResult<usize> {
+        return self.call_malloc(size, alignment);
+    }
+
+    fn realloc(&self, original: usize, size: usize, alignment: u32) -> ReflectionResult<usize> {
+        return self.call_realloc(original, size, alignment);
+    }
+
+    /**
+     * Freeing only fails when GMalloc itself can not be read, in which case nothing could have been allocated from it either
+     */
+    fn free(&self, address: usize) {
+        let _ = self.call_free(address);
+    }
+}
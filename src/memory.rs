@@ -0,0 +1,420 @@
+use crate::error::{ReflectionError, ReflectionResult};
+
+/**
+ * Source of raw memory for everything that interprets GNames, GObjects and UObjects
+ * Implemented by ProcessMemory for reading the live game from inside the injected DLL, and by SnapshotMemory for reading byte buffers captured from (or built to look like) the game
+ */
+pub trait MemoryReader {
+    /**
+     * Copies buf.len() bytes starting at address into buf, returns false if any part of the range can not be read
+     */
+    fn read_bytes(&self, address: usize, buf: &mut [u8]) -> bool;
+
+    /**
+     * Same as read_bytes, but reports the failed range as an error
+     */
+    fn read_exact(&self, address: usize, buf: &mut [u8]) -> ReflectionResult<()> {
+        if !self.read_bytes(address, buf) {
+            return Err(ReflectionError::Unreadable { address: address, len: buf.len() });
+        }
+        return Ok(());
+    }
+
+    fn read_u8(&self, address: usize) -> ReflectionResult<u8> {
+        let mut buf = [0u8; 1];
+        self.read_exact(address, &mut buf)?;
+        return Ok(buf[0]);
+    }
+
+    fn read_u16(&self, address: usize) -> ReflectionResult<u16> {
+        let mut buf = [0u8; 2];
+        self.read_exact(address, &mut buf)?;
+        return Ok(u16::from_le_bytes(buf));
+    }
+
+    fn read_u32(&self, address: usize) -> ReflectionResult<u32> {
+        let mut buf = [0u8; 4];
+        self.read_exact(address, &mut buf)?;
+        return Ok(u32::from_le_bytes(buf));
+    }
+
+    fn read_i32(&self, address: usize) -> ReflectionResult<i32> {
+        let mut buf = [0u8; 4];
+        self.read_exact(address, &mut buf)?;
+        return Ok(i32::from_le_bytes(buf));
+    }
+
+    fn read_u64(&self, address: usize) -> ReflectionResult<u64> {
+        let mut buf = [0u8; 8];
+        self.read_exact(address, &mut buf)?;
+        return Ok(u64::from_le_bytes(buf));
+    }
+
+    fn read_f32(&self, address: usize) -> ReflectionResult<f32> {
+        let mut buf = [0u8; 4];
+        self.read_exact(address, &mut buf)?;
+        return Ok(f32::from_le_bytes(buf));
+    }
+
+    /**
+     * Reads a pointer sized value, the game is 64 bit so this is always 8 bytes regardless of the host
+     */
+    fn read_usize(&self, address: usize) -> ReflectionResult<usize> {
+        return self.read_u64(address).map(|value| value as usize);
+    }
+
+    /**
+     * Reads a pointer that has to be followed, a null pointer is reported as an error instead of being returned
+     */
+    fn read_pointer(&self, address: usize) -> ReflectionResult<usize> {
+        let pointer: usize = self.read_usize(address)?;
+        if pointer == 0 {
+            return Err(ReflectionError::NullPointer { address: address });
+        }
+        return Ok(pointer);
+    }
+}
+
+/**
+ * Memory that can also be changed, used by the property writer and anything that builds params buffers
+ * Writes go through &mut so a reader borrowed out of the same memory can not observe a half finished change
+ */
+pub trait MemoryWriter: MemoryReader {
+    /**
+     * Copies data to address, returns false without writing anything if any part of the range can not be written
+     */
+    fn write_bytes(&mut self, address: usize, data: &[u8]) -> bool;
+
+    /**
+     * Same as write_bytes, but reports the failed range as an error
+     */
+    fn write_exact(&mut self, address: usize, data: &[u8]) -> ReflectionResult<()> {
+        if !self.write_bytes(address, data) {
+            return Err(ReflectionError::Unwritable { address: address, len: data.len() });
+        }
+        return Ok(());
+    }
+
+    fn write_u8(&mut self, address: usize, value: u8) -> ReflectionResult<()> {
+        return self.write_exact(address, &[value]);
+    }
+
+    fn write_u32(&mut self, address: usize, value: u32) -> ReflectionResult<()> {
+        return self.write_exact(address, &value.to_le_bytes());
+    }
+
+    fn write_i32(&mut self, address: usize, value: i32) -> ReflectionResult<()> {
+        return self.write_exact(address, &value.to_le_bytes());
+    }
+
+    fn write_f32(&mut self, address: usize, value: f32) -> ReflectionResult<()> {
+        return self.write_exact(address, &value.to_le_bytes());
+    }
+
+    /**
+     * Writes a pointer sized value, always 8 bytes to match read_usize
+     */
+    fn write_usize(&mut self, address: usize, value: usize) -> ReflectionResult<()> {
+        return self.write_exact(address, &(value as u64).to_le_bytes());
+    }
+}
+
+/**
+ * Reads memory of the process the DLL is injected into by dereferencing addresses directly
+ * Every read is checked against the page protection first, so stale pointers into freed memory fail instead of crashing the game
+ */
+pub struct ProcessMemory;
+
+impl MemoryReader for ProcessMemory {
+    fn read_bytes(&self, address: usize, buf: &mut [u8]) -> bool {
+        if address == 0 || !is_readable(address, buf.len()) {
+            return false;
+        }
+
+        unsafe {
+            std::ptr::copy_nonoverlapping(address as *const u8, buf.as_mut_ptr(), buf.len());
+        }
+
+        return true;
+    }
+}
+
+impl MemoryWriter for ProcessMemory {
+    fn write_bytes(&mut self, address: usize, data: &[u8]) -> bool {
+        if address == 0 || !is_writable(address, data.len()) {
+            return false;
+        }
+
+        unsafe {
+            std::ptr::copy_nonoverlapping(data.as_ptr(), address as *mut u8, data.len());
+        }
+
+        return true;
+    }
+}
+
+#[cfg(windows)]
+#[repr(C)]
+struct MemoryBasicInformation {
+    base_address: usize,
+    allocation_base: usize,
+    allocation_protect: u32,
+    partition_id: u16,
+    region_size: usize,
+    state: u32,
+    protect: u32,
+    kind: u32
+}
+
+#[cfg(windows)]
+#[link(name = "kernel32")]
+extern "system" {
+    fn VirtualQuery(address: *const u8, buffer: *mut MemoryBasicInformation, length: usize) -> usize;
+}
+
+#[cfg(windows)]
+const MEM_COMMIT: u32 = 0x1000;
+#[cfg(windows)]
+const PAGE_GUARD: u32 = 0x100;
+#[cfg(windows)]
+const PAGE_READABLE: u32 = 0x02 | 0x04 | 0x08 | 0x20 | 0x40 | 0x80;
+#[cfg(windows)]
+const PAGE_WRITABLE: u32 = 0x04 | 0x08 | 0x40 | 0x80;
+
+#[cfg(windows)]
+fn is_readable(address: usize, len: usize) -> bool {
+    return has_protection(address, len, PAGE_READABLE);
+}
+
+#[cfg(windows)]
+fn is_writable(address: usize, len: usize) -> bool {
+    return has_protection(address, len, PAGE_WRITABLE);
+}
+
+/**
+ * Walks every page region overlapping the range and checks that it is committed and allows one of the protections in mask
+ */
+#[cfg(windows)]
+fn has_protection(address: usize, len: usize, mask: u32) -> bool {
+    let end: usize = match address.checked_add(len) {
+        Some(end) => end,
+        None => return false
+    };
+
+    let mut current: usize = address;
+
+    while current < end {
+        let mut info: MemoryBasicInformation = unsafe { std::mem::zeroed() };
+
+        let written = unsafe { VirtualQuery(current as *const u8, &mut info, std::mem::size_of::<MemoryBasicInformation>()) };
+
+        if written == 0 {
+            return false;
+        }
+
+        if info.state != MEM_COMMIT || info.protect & PAGE_GUARD != 0 || info.protect & mask == 0 {
+            return false;
+        }
+
+        current = info.base_address + info.region_size;
+    }
+
+    return true;
+}
+
+#[cfg(not(windows))]
+fn is_readable(address: usize, len: usize) -> bool {
+    return address.checked_add(len).is_some();
+}
+
+#[cfg(not(windows))]
+fn is_writable(address: usize, len: usize) -> bool {
+    return address.checked_add(len).is_some();
+}
+
+/**
+ * A contiguous block of memory inside a SnapshotMemory, starting at base in the address space being imitated
+ */
+pub struct MemoryRegion {
+    pub base: usize,
+    pub bytes: Vec<u8>
+}
+
+impl MemoryRegion {
+    fn contains(&self, address: usize, len: usize) -> bool {
+        if address < self.base {
+            return false;
+        }
+
+        return match (address - self.base).checked_add(len) {
+            Some(end) => end <= self.bytes.len(),
+            None => false
+        };
+    }
+}
+
+/**
+ * Memory image made of byte buffers placed at fixed addresses
+ * Used to run the reflection code against memory captured from the game, or against hand built images outside of the game entirely
+ */
+pub struct SnapshotMemory {
+    regions: Vec<MemoryRegion>
+}
+
+impl Default for SnapshotMemory {
+    fn default() -> SnapshotMemory {
+        return SnapshotMemory::new();
+    }
+}
+
+impl SnapshotMemory {
+    pub fn new() -> SnapshotMemory {
+        return SnapshotMemory { regions: Vec::new() };
+    }
+
+    pub fn regions(&self) -> &Vec<MemoryRegion> {
+        return &self.regions;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.regions.is_empty();
+    }
+
+    /**
+     * Maps bytes at base, regions are expected not to overlap
+     */
+    pub fn add_region(&mut self, base: usize, bytes: Vec<u8>) {
+        self.regions.push(MemoryRegion { base: base, bytes: bytes });
+    }
+
+    /**
+     * Copies len bytes at base out of another reader (usually ProcessMemory) into a new region
+     */
+    pub fn capture_region(&mut self, source: &dyn MemoryReader, base: usize, len: usize) -> bool {
+        let mut bytes: Vec<u8> = vec![0u8; len];
+
+        if !source.read_bytes(base, &mut bytes) {
+            return false;
+        }
+
+        self.add_region(base, bytes);
+
+        return true;
+    }
+}
+
+impl MemoryReader for SnapshotMemory {
+    fn read_bytes(&self, address: usize, buf: &mut [u8]) -> bool {
+        for region in self.regions.iter() {
+            if region.contains(address, buf.len()) {
+                let start = address - region.base;
+                buf.copy_from_slice(&region.bytes[start..start + buf.len()]);
+                return true;
+            }
+        }
+
+        return false;
+    }
+}
+
+impl MemoryWriter for SnapshotMemory {
+    /**
+     * Overwrites bytes inside an already mapped region, writes never map new memory
+     */
+    fn write_bytes(&mut self, address: usize, data: &[u8]) -> bool {
+        for region in self.regions.iter_mut() {
+            if region.contains(address, data.len()) {
+                let start = address - region.base;
+                region.bytes[start..start + data.len()].copy_from_slice(data);
+                return true;
+            }
+        }
+
+        return false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_regions() -> SnapshotMemory {
+        let mut mem = SnapshotMemory::new();
+        mem.add_region(0x1000, vec![1, 2, 3, 4]);
+        mem.add_region(0x1004, vec![5, 6, 7, 8]);
+        return mem;
+    }
+
+    #[test]
+    fn snapshot_reads_stay_inside_one_region() {
+        let mem = two_regions();
+        let mut buf = [0u8; 4];
+
+        assert!(mem.read_bytes(0x1000, &mut buf));
+        assert_eq!(buf, [1, 2, 3, 4]);
+        assert!(mem.read_bytes(0x1004, &mut buf));
+        assert_eq!(buf, [5, 6, 7, 8]);
+        assert_eq!(mem.read_u8(0x1007).unwrap(), 8);
+
+        // Adjacent regions are not stitched together, a read has to fit in one of them
+        assert!(!mem.read_bytes(0x1002, &mut buf));
+        assert!(!mem.read_bytes(0x0fff, &mut [0u8; 1]));
+        assert!(!mem.read_bytes(0x1008, &mut [0u8; 1]));
+        assert!(!mem.read_bytes(0x1005, &mut buf));
+        assert!(mem.read_bytes(0x1008, &mut []));
+        assert!(!mem.read_bytes(usize::MAX, &mut [0u8; 2]));
+
+        match mem.read_u32(0x1006) {
+            Err(ReflectionError::Unreadable { address, len }) => assert_eq!((address, len), (0x1006, 4)),
+            other => panic!("expected Unreadable, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn snapshot_writes_never_map_memory() {
+        let mut mem = two_regions();
+
+        mem.write_u8(0x1007, 0xAA).unwrap();
+        assert_eq!(mem.read_u8(0x1007).unwrap(), 0xAA);
+
+        // A straddling write fails as a whole and leaves both regions untouched
+        assert!(!mem.write_bytes(0x1002, &[9, 9, 9, 9]));
+        assert_eq!(mem.read_u32(0x1000).unwrap(), u32::from_le_bytes([1, 2, 3, 4]));
+        assert_eq!(mem.read_u8(0x1004).unwrap(), 5);
+
+        assert!(mem.write_u32(0x2000, 1).is_err());
+        assert!(mem.read_u8(0x2000).is_err());
+        assert_eq!(mem.regions().len(), 2);
+    }
+
+    #[test]
+    fn null_pointers_are_errors() {
+        let mut mem = SnapshotMemory::new();
+        mem.add_region(0x1000, vec![0u8; 16]);
+        mem.write_usize(0x1008, 0x1234).unwrap();
+
+        assert!(matches!(mem.read_pointer(0x1000), Err(ReflectionError::NullPointer { address: 0x1000 })));
+        assert_eq!(mem.read_pointer(0x1008).unwrap(), 0x1234);
+        assert_eq!(mem.read_usize(0x1000).unwrap(), 0);
+    }
+
+    #[test]
+    fn process_memory_checks_the_range() {
+        let mut local: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+        let address: usize = local.as_mut_ptr() as usize;
+        let mut process = ProcessMemory;
+
+        assert_eq!(process.read_u32(address + 4).unwrap(), u32::from_le_bytes([5, 6, 7, 8]));
+        process.write_u8(address, 0xFF).unwrap();
+        assert!(!process.read_bytes(0, &mut [0u8; 1]));
+        assert!(!process.read_bytes(usize::MAX - 1, &mut [0u8; 4]));
+        assert!(!process.write_bytes(0, &[0]));
+
+        let mut snapshot = SnapshotMemory::new();
+        assert!(snapshot.capture_region(&process, address, local.len()));
+        assert!(!snapshot.capture_region(&process, 0, 4));
+        assert_eq!(snapshot.regions().len(), 1);
+        assert_eq!(snapshot.read_u8(address).unwrap(), 0xFF);
+        assert_eq!(local[0], 0xFF);
+    }
+}
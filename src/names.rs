@@ -0,0 +1,316 @@
+use std::collections::HashMap;
+
+use crate::error::{ReflectionError, ReflectionResult};
+use crate::memory::MemoryReader;
+use crate::reflection::TArray;
+
+/**
+ * FNameEntry layout, the Index field holds the GNames index shifted left by one with the wide flag in the low bit
+ */
+const FNAME_ENTRY_INDEX_OFFSET: usize = 0x8;
+const FNAME_ENTRY_STRING_OFFSET: usize = 0x18;
+const NAME_WIDE_MASK: u32 = 0x1;
+
+/**
+ * NAME_SIZE in UE3, no entry can be longer than this so anything past it is garbage
+ */
+const NAME_SIZE: usize = 1024;
+
+/**
+ * An FName as stored inside UObjects and properties, a GNames index followed by the instance number
+ * The number is stored one higher than what is displayed, 0 means no suffix at all
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct FName {
+    pub index: u32,
+    pub number: u32
+}
+
+impl FName {
+    pub fn read(mem: &dyn MemoryReader, address: usize) -> ReflectionResult<FName> {
+        return Ok(FName { index: mem.read_u32(address)?, number: mem.read_u32(address + 0x4)? });
+    }
+
+    /**
+     * Appends the _N instance suffix to an already resolved GNames entry, same as FName::ToString
+     */
+    pub fn with_suffix(&self, base: &str) -> String {
+        if self.number == 0 {
+            return base.to_string();
+        }
+
+        return format!("{}_{}", base, self.number - 1);
+    }
+
+    /**
+     * Resolves the full name, including the instance suffix, e.g. PoplarPlayerController_3
+     */
+    pub fn resolve(&self, mem: &dyn MemoryReader, gnames: TArray) -> ReflectionResult<String> {
+        return Ok(self.with_suffix(&get_fname_from_gnames_at_idx(mem, gnames, self.index as usize)?));
+    }
+}
+
+/**
+ * Reads the string of a single GNames entry, without any instance suffix
+ */
+pub fn get_fname_from_gnames_at_idx(mem: &dyn MemoryReader, gnames: TArray, idx: usize) -> ReflectionResult<String>{
+    let entry: usize = match gnames.get(mem, idx) {
+        Ok(entry) => entry,
+        Err(ReflectionError::IndexOutOfRange { index, count }) => return Err(ReflectionError::NameIndexOutOfRange { index: index, count: count }),
+        Err(error) => return Err(error)
+    };
+
+    if entry == 0 {
+        return Err(ReflectionError::NullPointer { address: gnames.address });
+    }
+
+    let header: u32 = mem.read_u32(entry + FNAME_ENTRY_INDEX_OFFSET)?;
+
+    if header & NAME_WIDE_MASK != 0 {
+        return read_wide_string(mem, entry + FNAME_ENTRY_STRING_OFFSET, NAME_SIZE);
+    }
+
+    return read_ansi_string(mem, entry + FNAME_ENTRY_STRING_OFFSET, NAME_SIZE);
+}
+
+/**
+ * How many bytes the FNameEntry at entry takes up to and including the terminator of its string
+ */
+pub fn fname_entry_size(mem: &dyn MemoryReader, entry: usize) -> ReflectionResult<usize> {
+    let unit_size: usize = if mem.read_u32(entry + FNAME_ENTRY_INDEX_OFFSET)? & NAME_WIDE_MASK != 0 { 2 } else { 1 };
+
+    for idx in 0..NAME_SIZE {
+        let address: usize = entry + FNAME_ENTRY_STRING_OFFSET + (idx * unit_size);
+        let unit: u16 = if unit_size == 2 { mem.read_u16(address)? } else { mem.read_u8(address)? as u16 };
+
+        if unit == 0 {
+            return Ok(FNAME_ENTRY_STRING_OFFSET + ((idx + 1) * unit_size));
+        }
+    }
+
+    return Err(ReflectionError::InvalidString { address: entry + FNAME_ENTRY_STRING_OFFSET });
+}
+
+/**
+ * Interned copy of GNames, index to name and name to index are both a single lookup
+ * Names are matched case insensitively like the engine does, so "playercontroller" finds PlayerController
+ */
+pub struct NameTable {
+    names: Vec<Option<String>>,
+    indices: HashMap<String, u32>
+}
+
+impl Default for NameTable {
+    fn default() -> NameTable {
+        return NameTable::new();
+    }
+}
+
+impl NameTable {
+    pub fn new() -> NameTable {
+        return NameTable { names: Vec::new(), indices: HashMap::new() };
+    }
+
+    pub fn build(mem: &dyn MemoryReader, gnames: TArray) -> ReflectionResult<NameTable> {
+        let mut table: NameTable = NameTable::new();
+        table.refresh(mem, gnames)?;
+        return Ok(table);
+    }
+
+    /**
+     * Reads every GNames entry added since the last refresh, returns how many were added
+     * GNames only ever grows, so entries that were already read are never looked at again
+     * Entries that fail to read (null slots, garbage) are kept as None so indices stay aligned
+     */
+    pub fn refresh(&mut self, mem: &dyn MemoryReader, gnames: TArray) -> ReflectionResult<usize> {
+        let count: usize = gnames.len(mem)?;
+        let previous: usize = self.names.len();
+
+        for idx in previous..count {
+            let maybe_name: Option<String> = get_fname_from_gnames_at_idx(mem, gnames, idx).ok();
+
+            if let Some(name) = &maybe_name {
+                self.indices.entry(name.to_lowercase()).or_insert(idx as u32);
+            }
+
+            self.names.push(maybe_name);
+        }
+
+        return Ok(self.names.len().saturating_sub(previous));
+    }
+
+    pub fn len(&self) -> usize {
+        return self.names.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.names.is_empty();
+    }
+
+    pub fn get(&self, index: u32) -> Option<&str> {
+        return self.names.get(index as usize)?.as_deref();
+    }
+
+    pub fn find(&self, name: &str) -> Option<u32> {
+        return self.indices.get(&name.to_lowercase()).copied();
+    }
+
+    /**
+     * Resolves an FName back to its display string, including the instance suffix
+     */
+    pub fn resolve(&self, fname: FName) -> Option<String> {
+        return Some(fname.with_suffix(self.get(fname.index)?));
+    }
+
+    /**
+     * Turns a display string into an FName the same way the engine's FName constructor does
+     * A trailing _N is always split off into the instance number (Foo_3 becomes Foo with number 4), so a name entry that literally ends in _N is never looked up, just like FName(TEXT("Foo_3")) never finds it
+     */
+    pub fn find_fname(&self, name: &str) -> Option<FName> {
+        if let Some((base, number)) = split_number(name) {
+            return Some(FName { index: self.find(base)?, number: number });
+        }
+
+        return Some(FName { index: self.find(name)?, number: 0 });
+    }
+}
+
+/**
+ * Splits a trailing _N off a name the way FName::SplitNameWithCheck does, None when there is no number or it has a leading zero (Foo_03 stays one name)
+ */
+fn split_number(name: &str) -> Option<(&str, u32)> {
+    let (base, suffix) = name.rsplit_once('_')?;
+
+    if base.is_empty() || suffix.is_empty() || (suffix.len() > 1 && suffix.starts_with('0')) || !suffix.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    return Some((base, suffix.parse::<u32>().ok()?.checked_add(1)?));
+}
+
+/**
+ * Reads a null terminated string of 1 byte characters (Latin-1, the way UE3 stores ANSI names), reading in chunks so long names do not cost a read per character
+ * Chunks that run off the end of a readable page fall back to single byte reads, so a name right before an unmapped page still decodes
+ */
+pub fn read_ansi_string(mem: &dyn MemoryReader, address: usize, max_len: usize) -> ReflectionResult<String> {
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut chunk = [0u8; 64];
+
+    while bytes.len() < max_len {
+        let current: usize = address + bytes.len();
+
+        if mem.read_bytes(current, &mut chunk) {
+            match chunk.iter().position(|byte| *byte == 0) {
+                Some(end) => {
+                    bytes.extend_from_slice(&chunk[..end]);
+                    return Ok(latin1(&bytes));
+                }
+                None => bytes.extend_from_slice(&chunk)
+            }
+        }
+        else {
+            let byte: u8 = mem.read_u8(current)?;
+            if byte == 0 {
+                return Ok(latin1(&bytes));
+            }
+            bytes.push(byte);
+        }
+    }
+
+    return Err(ReflectionError::InvalidString { address: address });
+}
+
+/**
+ * Every byte is the code point of the same value, so no ANSI name fails to decode
+ */
+fn latin1(bytes: &[u8]) -> String {
+    return bytes.iter().map(|byte| *byte as char).collect();
+}
+
+/**
+ * Reads a null terminated UTF-16 string, max_len is in characters
+ */
+pub fn read_wide_string(mem: &dyn MemoryReader, address: usize, max_len: usize) -> ReflectionResult<String> {
+    let mut units: Vec<u16> = Vec::new();
+
+    while units.len() < max_len {
+        let unit: u16 = mem.read_u16(address + (units.len() * 2))?;
+        if unit == 0 {
+            return String::from_utf16(&units).map_err(|_| ReflectionError::InvalidString { address: address });
+        }
+        units.push(unit);
+    }
+
+    return Err(ReflectionError::InvalidString { address: address });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestImage;
+
+    #[test]
+    fn decodes_ansi_and_wide_entries() {
+        let mut image: TestImage = TestImage::new();
+        let ansi: u32 = image.add_name("PlayerController");
+        let wide: u32 = image.add_wide_name("Wïde");
+
+        assert_eq!(get_fname_from_gnames_at_idx(&image.mem, image.gnames, 0).unwrap(), "None");
+        assert_eq!(get_fname_from_gnames_at_idx(&image.mem, image.gnames, ansi as usize).unwrap(), "PlayerController");
+        assert_eq!(get_fname_from_gnames_at_idx(&image.mem, image.gnames, wide as usize).unwrap(), "Wïde");
+        assert!(matches!(get_fname_from_gnames_at_idx(&image.mem, image.gnames, 99), Err(ReflectionError::NameIndexOutOfRange { index: 99, .. })));
+    }
+
+    #[test]
+    fn ansi_entries_are_latin1() {
+        let mut image: TestImage = TestImage::new();
+        let index: u32 = image.add_raw_name(&[b'C', 0xE9, b'l', b'i', b'n', b'e']);
+
+        assert_eq!(get_fname_from_gnames_at_idx(&image.mem, image.gnames, index as usize).unwrap(), "Céline");
+    }
+
+    #[test]
+    fn resolves_instance_suffixes() {
+        let mut image: TestImage = TestImage::new();
+        let index: u32 = image.add_name("PoplarPlayerController");
+
+        assert_eq!(FName { index: index, number: 0 }.resolve(&image.mem, image.gnames).unwrap(), "PoplarPlayerController");
+        assert_eq!(FName { index: index, number: 4 }.resolve(&image.mem, image.gnames).unwrap(), "PoplarPlayerController_3");
+    }
+
+    #[test]
+    fn name_table_finds_names_case_insensitively() {
+        let mut image: TestImage = TestImage::new();
+        let index: u32 = image.add_name("PlayerController");
+        let mut table: NameTable = NameTable::build(&image.mem, image.gnames).unwrap();
+
+        assert_eq!(table.find("playercontroller"), Some(index));
+        assert_eq!(table.find_fname("PlayerController_3"), Some(FName { index: index, number: 4 }));
+        assert_eq!(table.find_fname("PlayerController_03"), None);
+        assert_eq!(table.resolve(FName { index: index, number: 1 }).as_deref(), Some("PlayerController_0"));
+
+        let added: u32 = image.add_name("Camera");
+        assert_eq!(table.refresh(&image.mem, image.gnames).unwrap(), 1);
+        assert_eq!(table.find("camera"), Some(added));
+    }
+
+    #[test]
+    fn name_suffixes_are_split_before_lookup() {
+        let mut image: TestImage = TestImage::new();
+        let base: u32 = image.add_name("Foo");
+        let literal: u32 = image.add_name("Foo_3");
+        image.add_name("Bar_2");
+        image.add_name("Baz_07");
+        let table: NameTable = NameTable::build(&image.mem, image.gnames).unwrap();
+
+        // Both Foo and Foo_3 are in GNames, the engine still builds Foo with number 4
+        assert_eq!(table.find("Foo_3"), Some(literal));
+        assert_eq!(table.find_fname("Foo_3"), Some(FName { index: base, number: 4 }));
+
+        // Splitting does not fall back to the literal entry when the base does not exist
+        assert_eq!(table.find_fname("Bar_2"), None);
+        assert_eq!(table.find_fname("Baz_07").map(|fname| fname.number), Some(0));
+        assert_eq!(table.find_fname("Foo_4294967295"), None);
+        assert_eq!(table.find_fname("Foo_"), None);
+    }
+}
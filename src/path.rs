@@ -0,0 +1,117 @@
+use std::fmt;
+
+/**
+ * A parsed object name in the engine's format, either a path name (Engine.PlayerController.SetFOV) or a full name (Function Engine.PlayerController.SetFOV)
+ * outers runs from the outermost package inwards, the same order the path name is written in
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ObjectPath {
+    pub class: Option<String>,
+    pub outers: Vec<String>,
+    pub name: String
+}
+
+impl ObjectPath {
+    /**
+     * Parses a path or full name, both . and the : subobject delimiter separate outers
+     * Returns None for empty strings and names with empty segments such as Engine..SetFOV
+     */
+    pub fn parse(text: &str) -> Option<ObjectPath> {
+        let text: &str = text.trim();
+
+        let (class, path) = match text.split_once(' ') {
+            Some((class, path)) => (Some(class.to_string()), path.trim()),
+            None => (None, text)
+        };
+
+        let mut segments: Vec<String> = Vec::new();
+
+        for segment in path.split(['.', ':']) {
+            if segment.is_empty() || segment.contains(char::is_whitespace) {
+                return None;
+            }
+            segments.push(segment.to_string());
+        }
+
+        let name: String = segments.pop()?;
+
+        return Some(ObjectPath { class: class, outers: segments, name: name });
+    }
+
+    pub fn path_name(&self) -> String {
+        let mut path: String = String::new();
+
+        for outer in self.outers.iter() {
+            path.push_str(outer);
+            path.push('.');
+        }
+
+        path.push_str(&self.name);
+
+        return path;
+    }
+
+    /**
+     * The path with . between every segment, the form paths are indexed and compared in
+     * Anything that does not parse as a path name (full names included) is returned as it is
+     */
+    pub fn normalize(text: &str) -> String {
+        return match ObjectPath::parse(text) {
+            Some(path) if path.class.is_none() => path.path_name(),
+            _ => text.to_string()
+        };
+    }
+
+    /**
+     * The path of the directly enclosing outer, None for top level packages
+     * The class is dropped as it is not known for the outer
+     */
+    pub fn outer(&self) -> Option<ObjectPath> {
+        let mut outers: Vec<String> = self.outers.clone();
+        let name: String = outers.pop()?;
+
+        return Some(ObjectPath { class: None, outers: outers, name: name });
+    }
+
+    /**
+     * The outermost package the object lives in, the object's own name for packages
+     */
+    pub fn package(&self) -> &str {
+        return self.outers.first().unwrap_or(&self.name);
+    }
+}
+
+impl fmt::Display for ObjectPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match &self.class {
+            Some(class) => write!(f, "{} {}", class, self.path_name()),
+            None => write!(f, "{}", self.path_name())
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_path_and_full_names() {
+        let path: ObjectPath = ObjectPath::parse("Function Engine.PlayerController:SetFOV").unwrap();
+
+        assert_eq!(path.class.as_deref(), Some("Function"));
+        assert_eq!(path.outers, vec!["Engine", "PlayerController"]);
+        assert_eq!(path.name, "SetFOV");
+        assert_eq!(path.package(), "Engine");
+        assert_eq!(path.outer().unwrap().path_name(), "Engine.PlayerController");
+        assert_eq!(path.to_string(), "Function Engine.PlayerController.SetFOV");
+        assert_eq!(ObjectPath::parse("Engine..SetFOV"), None);
+        assert_eq!(ObjectPath::parse(""), None);
+    }
+
+    #[test]
+    fn normalizes_subobject_delimiters() {
+        assert_eq!(ObjectPath::normalize("TheWorld:PersistentLevel.Pawn_0"), "TheWorld.PersistentLevel.Pawn_0");
+        assert_eq!(ObjectPath::normalize("Engine.PlayerController"), "Engine.PlayerController");
+        assert_eq!(ObjectPath::normalize("Class Engine.PlayerController"), "Class Engine.PlayerController");
+    }
+}
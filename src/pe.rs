@@ -0,0 +1,668 @@
+use std::fmt;
+use std::ops::Range;
+
+use crate::error::{ReflectionError, ReflectionResult};
+use crate::memory::MemoryReader;
+
+/**
+ * PE32+ header layout, offsets are from the start of the structure they belong to
+ */
+const IMAGE_DOS_SIGNATURE: u16 = 0x5A4D;
+const IMAGE_NT_SIGNATURE: u32 = 0x00004550;
+const IMAGE_NT_OPTIONAL_HDR64_MAGIC: u16 = 0x20B;
+const DOS_E_LFANEW_OFFSET: usize = 0x3C;
+const NT_MACHINE_OFFSET: usize = 0x4;
+const NT_NUMBER_OF_SECTIONS_OFFSET: usize = 0x6;
+const NT_TIME_DATE_STAMP_OFFSET: usize = 0x8;
+const NT_SIZE_OF_OPTIONAL_HEADER_OFFSET: usize = 0x14;
+const NT_CHARACTERISTICS_OFFSET: usize = 0x16;
+const NT_OPTIONAL_HEADER_OFFSET: usize = 0x18;
+const OPTIONAL_MAGIC_OFFSET: usize = 0x0;
+const OPTIONAL_ENTRY_POINT_OFFSET: usize = 0x10;
+const OPTIONAL_IMAGE_BASE_OFFSET: usize = 0x18;
+const OPTIONAL_SIZE_OF_IMAGE_OFFSET: usize = 0x38;
+const OPTIONAL_SIZE_OF_HEADERS_OFFSET: usize = 0x3C;
+const OPTIONAL_NUMBER_OF_RVA_AND_SIZES_OFFSET: usize = 0x6C;
+const OPTIONAL_DATA_DIRECTORY_OFFSET: usize = 0x70;
+const DATA_DIRECTORY_SIZE: usize = 0x8;
+const SECTION_HEADER_SIZE: usize = 0x28;
+const SECTION_NAME_SIZE: usize = 0x8;
+const SECTION_VIRTUAL_SIZE_OFFSET: usize = 0x8;
+const SECTION_VIRTUAL_ADDRESS_OFFSET: usize = 0xC;
+const SECTION_SIZE_OF_RAW_DATA_OFFSET: usize = 0x10;
+const SECTION_POINTER_TO_RAW_DATA_OFFSET: usize = 0x14;
+const SECTION_CHARACTERISTICS_OFFSET: usize = 0x24;
+pub const IMAGE_SCN_MEM_EXECUTE: u32 = 0x20000000;
+
+/**
+ * Data directory slots
+ */
+pub const IMAGE_DIRECTORY_ENTRY_EXPORT: usize = 0;
+pub const IMAGE_DIRECTORY_ENTRY_IMPORT: usize = 1;
+
+/**
+ * IMAGE_EXPORT_DIRECTORY
+ */
+const EXPORT_NAME_OFFSET: usize = 0xC;
+const EXPORT_BASE_OFFSET: usize = 0x10;
+const EXPORT_NUMBER_OF_FUNCTIONS_OFFSET: usize = 0x14;
+const EXPORT_NUMBER_OF_NAMES_OFFSET: usize = 0x18;
+const EXPORT_ADDRESS_OF_FUNCTIONS_OFFSET: usize = 0x1C;
+const EXPORT_ADDRESS_OF_NAMES_OFFSET: usize = 0x20;
+const EXPORT_ADDRESS_OF_NAME_ORDINALS_OFFSET: usize = 0x24;
+
+/**
+ * IMAGE_IMPORT_DESCRIPTOR, the table ends with an all zero descriptor
+ */
+const IMPORT_DESCRIPTOR_SIZE: usize = 0x14;
+const IMPORT_ORIGINAL_FIRST_THUNK_OFFSET: usize = 0x0;
+const IMPORT_NAME_OFFSET: usize = 0xC;
+const IMPORT_FIRST_THUNK_OFFSET: usize = 0x10;
+const IMAGE_ORDINAL_FLAG64: u64 = 0x8000000000000000;
+const THUNK_SIZE: usize = 0x8;
+
+/**
+ * Real images have a few dozen sections, imports and thousands of exports at most, anything past these is a header pointing into garbage
+ */
+const MAX_SECTIONS: usize = 96;
+const MAX_DATA_DIRECTORIES: usize = 16;
+const MAX_IMPORT_DESCRIPTORS: usize = 0x400;
+const MAX_THUNKS: usize = 0x10000;
+const MAX_EXPORTS: usize = 0x10000;
+const MAX_NAME_LEN: usize = 0x400;
+
+const PAGE_SIZE: usize = 0x1000;
+
+/**
+ * How the bytes are laid out
+ * Mapped is a module the way the loader maps it, offsets are RVAs
+ * File is the executable as it is on disk, RVAs go through the section table to find the raw data
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PeLayout {
+    Mapped,
+    File
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PeError {
+    /**
+     * A header or table runs past the end of the bytes, offset is where the read started
+     */
+    Truncated { offset: usize },
+    /**
+     * No MZ or PE signature, these are not the bytes of an image
+     */
+    InvalidSignature,
+    /**
+     * Only PE32+ (64 bit) images are understood
+     */
+    UnsupportedMagic { magic: u16 },
+    /**
+     * An RVA that lies in no section, or in the part of one that has no raw data on disk
+     */
+    UnmappedRva { rva: u32 },
+    /**
+     * A name that is not null terminated ASCII within MAX_NAME_LEN
+     */
+    InvalidName { rva: u32 },
+    /**
+     * A table with more entries than any real image has
+     */
+    TooManyEntries { rva: u32 }
+}
+
+impl fmt::Display for PeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            PeError::Truncated { offset } => write!(f, "image is truncated at {:x}", offset),
+            PeError::InvalidSignature => write!(f, "not a PE image"),
+            PeError::UnsupportedMagic { magic } => write!(f, "optional header magic {:x} is not PE32+", magic),
+            PeError::UnmappedRva { rva } => write!(f, "rva {:x} is not backed by any section", rva),
+            PeError::InvalidName { rva } => write!(f, "name at rva {:x} is not a valid string", rva),
+            PeError::TooManyEntries { rva } => write!(f, "table at rva {:x} has too many entries", rva)
+        };
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Section {
+    pub name: String,
+    pub virtual_address: u32,
+    pub virtual_size: u32,
+    pub raw_offset: u32,
+    pub raw_size: u32,
+    pub characteristics: u32
+}
+
+impl Section {
+    pub fn is_executable(&self) -> bool {
+        return self.characteristics & IMAGE_SCN_MEM_EXECUTE != 0;
+    }
+
+    /**
+     * RVAs the section covers once mapped, the larger of its virtual and raw size like the loader does
+     */
+    pub fn rva_range(&self) -> Range<usize> {
+        let start: usize = self.virtual_address as usize;
+
+        return start..start.saturating_add(self.virtual_size.max(self.raw_size) as usize);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct DataDirectory {
+    pub rva: u32,
+    pub size: u32
+}
+
+/**
+ * An exported function, forwarder is set instead of a real rva for exports that point into another module (NTDLL.RtlAllocateHeap)
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Export {
+    pub name: Option<String>,
+    pub ordinal: u32,
+    pub rva: u32,
+    pub forwarder: Option<String>
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImportName {
+    Name { hint: u16, name: String },
+    Ordinal(u16)
+}
+
+/**
+ * A function imported from a module, iat_rva is the slot the loader writes its address to
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedFunction {
+    pub name: ImportName,
+    pub iat_rva: u32
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Import {
+    pub module: String,
+    pub functions: Vec<ImportedFunction>
+}
+
+/**
+ * Headers of a PE32+ image over bytes borrowed from a file or a copy of a mapped module
+ * Exports and imports are only read when asked for
+ */
+pub struct PeImage<'a> {
+    bytes: &'a [u8],
+    layout: PeLayout,
+    pub machine: u16,
+    pub timestamp: u32,
+    pub characteristics: u16,
+    pub entry_point: u32,
+    pub image_base: u64,
+    pub size_of_image: u32,
+    pub size_of_headers: u32,
+    pub sections: Vec<Section>,
+    pub directories: Vec<DataDirectory>
+}
+
+impl<'a> PeImage<'a> {
+    pub fn parse(bytes: &'a [u8], layout: PeLayout) -> Result<PeImage<'a>, PeError> {
+        if read_u16(bytes, 0)? != IMAGE_DOS_SIGNATURE {
+            return Err(PeError::InvalidSignature);
+        }
+
+        let nt_headers: usize = read_u32(bytes, DOS_E_LFANEW_OFFSET)? as usize;
+
+        if read_u32(bytes, nt_headers)? != IMAGE_NT_SIGNATURE {
+            return Err(PeError::InvalidSignature);
+        }
+
+        let optional_header: usize = add_offset(nt_headers, NT_OPTIONAL_HEADER_OFFSET)?;
+        let magic: u16 = read_u16(bytes, add_offset(optional_header, OPTIONAL_MAGIC_OFFSET)?)?;
+
+        if magic != IMAGE_NT_OPTIONAL_HDR64_MAGIC {
+            return Err(PeError::UnsupportedMagic { magic: magic });
+        }
+
+        let directory_count: usize = (read_u32(bytes, add_offset(optional_header, OPTIONAL_NUMBER_OF_RVA_AND_SIZES_OFFSET)?)? as usize).min(MAX_DATA_DIRECTORIES);
+        let mut directories: Vec<DataDirectory> = Vec::with_capacity(directory_count);
+
+        for idx in 0..directory_count {
+            let directory: usize = add_offset(optional_header, OPTIONAL_DATA_DIRECTORY_OFFSET + (idx * DATA_DIRECTORY_SIZE))?;
+            directories.push(DataDirectory { rva: read_u32(bytes, directory)?, size: read_u32(bytes, add_offset(directory, 4)?)? });
+        }
+
+        let section_count: usize = read_u16(bytes, add_offset(nt_headers, NT_NUMBER_OF_SECTIONS_OFFSET)?)? as usize;
+
+        if section_count > MAX_SECTIONS {
+            return Err(PeError::TooManyEntries { rva: nt_headers as u32 });
+        }
+
+        let section_table: usize = add_offset(optional_header, read_u16(bytes, add_offset(nt_headers, NT_SIZE_OF_OPTIONAL_HEADER_OFFSET)?)? as usize)?;
+        let mut sections: Vec<Section> = Vec::with_capacity(section_count);
+
+        for idx in 0..section_count {
+            let section: usize = add_offset(section_table, idx * SECTION_HEADER_SIZE)?;
+            let name: &[u8] = bytes.get(section..add_offset(section, SECTION_NAME_SIZE)?).ok_or(PeError::Truncated { offset: section })?;
+
+            sections.push(Section {
+                name: String::from_utf8_lossy(name).trim_end_matches('\0').to_string(),
+                virtual_address: read_u32(bytes, add_offset(section, SECTION_VIRTUAL_ADDRESS_OFFSET)?)?,
+                virtual_size: read_u32(bytes, add_offset(section, SECTION_VIRTUAL_SIZE_OFFSET)?)?,
+                raw_offset: read_u32(bytes, add_offset(section, SECTION_POINTER_TO_RAW_DATA_OFFSET)?)?,
+                raw_size: read_u32(bytes, add_offset(section, SECTION_SIZE_OF_RAW_DATA_OFFSET)?)?,
+                characteristics: read_u32(bytes, add_offset(section, SECTION_CHARACTERISTICS_OFFSET)?)?
+            });
+        }
+
+        return Ok(PeImage {
+            bytes: bytes,
+            layout: layout,
+            machine: read_u16(bytes, add_offset(nt_headers, NT_MACHINE_OFFSET)?)?,
+            timestamp: read_u32(bytes, add_offset(nt_headers, NT_TIME_DATE_STAMP_OFFSET)?)?,
+            characteristics: read_u16(bytes, add_offset(nt_headers, NT_CHARACTERISTICS_OFFSET)?)?,
+            entry_point: read_u32(bytes, add_offset(optional_header, OPTIONAL_ENTRY_POINT_OFFSET)?)?,
+            image_base: read_u64(bytes, add_offset(optional_header, OPTIONAL_IMAGE_BASE_OFFSET)?)?,
+            size_of_image: read_u32(bytes, add_offset(optional_header, OPTIONAL_SIZE_OF_IMAGE_OFFSET)?)?,
+            size_of_headers: read_u32(bytes, add_offset(optional_header, OPTIONAL_SIZE_OF_HEADERS_OFFSET)?)?,
+            sections: sections,
+            directories: directories
+        });
+    }
+
+    pub fn layout(&self) -> PeLayout {
+        return self.layout;
+    }
+
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        return self.sections.iter().find(|section| section.name == name);
+    }
+
+    /**
+     * The section an RVA falls into, None for the headers and anything past the last section
+     */
+    pub fn section_of(&self, rva: u32) -> Option<&Section> {
+        return self.sections.iter().find(|section| section.rva_range().contains(&(rva as usize)));
+    }
+
+    /**
+     * RVAs of the executable sections
+     */
+    pub fn code_sections(&self) -> Vec<Range<usize>> {
+        return self.sections.iter().filter(|section| section.is_executable()).map(|section| section.virtual_address as usize..(section.virtual_address as usize).saturating_add(section.virtual_size as usize)).collect();
+    }
+
+    pub fn directory(&self, idx: usize) -> Option<DataDirectory> {
+        return self.directories.get(idx).copied().filter(|directory| directory.rva != 0);
+    }
+
+    /**
+     * Where the byte at rva is in the underlying bytes
+     */
+    pub fn rva_to_offset(&self, rva: u32) -> Result<usize, PeError> {
+        if self.layout == PeLayout::Mapped || rva < self.size_of_headers {
+            return Ok(rva as usize);
+        }
+
+        let section: &Section = self.section_of(rva).ok_or(PeError::UnmappedRva { rva: rva })?;
+        let offset: u32 = rva - section.virtual_address;
+
+        if offset >= section.raw_size {
+            return Err(PeError::UnmappedRva { rva: rva });
+        }
+
+        return section.raw_offset.checked_add(offset).map(|offset| offset as usize).ok_or(PeError::UnmappedRva { rva: rva });
+    }
+
+    pub fn read_u16(&self, rva: u32) -> Result<u16, PeError> {
+        return read_u16(self.bytes, self.rva_to_offset(rva)?);
+    }
+
+    pub fn read_u32(&self, rva: u32) -> Result<u32, PeError> {
+        return read_u32(self.bytes, self.rva_to_offset(rva)?);
+    }
+
+    pub fn read_u64(&self, rva: u32) -> Result<u64, PeError> {
+        return read_u64(self.bytes, self.rva_to_offset(rva)?);
+    }
+
+    /**
+     * A null terminated ASCII name, the way export, import and module names are stored
+     */
+    pub fn read_name(&self, rva: u32) -> Result<String, PeError> {
+        let start: usize = self.rva_to_offset(rva)?;
+        let bytes: &[u8] = &self.bytes[start.min(self.bytes.len())..];
+        let len: usize = bytes.iter().take(MAX_NAME_LEN).position(|byte| *byte == 0).ok_or(PeError::InvalidName { rva: rva })?;
+
+        if !bytes[..len].is_ascii() {
+            return Err(PeError::InvalidName { rva: rva });
+        }
+
+        return Ok(String::from_utf8_lossy(&bytes[..len]).to_string());
+    }
+
+    /**
+     * Name the module was linked as, from the export directory
+     */
+    pub fn export_name(&self) -> Result<Option<String>, PeError> {
+        return match self.directory(IMAGE_DIRECTORY_ENTRY_EXPORT) {
+            Some(directory) => Ok(Some(self.read_name(self.read_u32(add_rva(directory.rva, EXPORT_NAME_OFFSET)?)?)?)),
+            None => Ok(None)
+        };
+    }
+
+    /**
+     * Every exported function in ordinal order, names attached where the name table has one
+     */
+    pub fn exports(&self) -> Result<Vec<Export>, PeError> {
+        let directory: DataDirectory = match self.directory(IMAGE_DIRECTORY_ENTRY_EXPORT) {
+            Some(directory) => directory,
+            None => return Ok(Vec::new())
+        };
+
+        let base: u32 = self.read_u32(add_rva(directory.rva, EXPORT_BASE_OFFSET)?)?;
+        let function_count: usize = self.read_u32(add_rva(directory.rva, EXPORT_NUMBER_OF_FUNCTIONS_OFFSET)?)? as usize;
+        let name_count: usize = self.read_u32(add_rva(directory.rva, EXPORT_NUMBER_OF_NAMES_OFFSET)?)? as usize;
+        let functions: u32 = self.read_u32(add_rva(directory.rva, EXPORT_ADDRESS_OF_FUNCTIONS_OFFSET)?)?;
+        let names: u32 = self.read_u32(add_rva(directory.rva, EXPORT_ADDRESS_OF_NAMES_OFFSET)?)?;
+        let name_ordinals: u32 = self.read_u32(add_rva(directory.rva, EXPORT_ADDRESS_OF_NAME_ORDINALS_OFFSET)?)?;
+
+        if function_count > MAX_EXPORTS || name_count > MAX_EXPORTS {
+            return Err(PeError::TooManyEntries { rva: directory.rva });
+        }
+
+        let mut function_names: Vec<Option<String>> = vec![None; function_count];
+
+        for idx in 0..name_count {
+            let function: usize = self.read_u16(add_rva(name_ordinals, idx * 2)?)? as usize;
+
+            if let Some(function_name) = function_names.get_mut(function) {
+                *function_name = Some(self.read_name(self.read_u32(add_rva(names, idx * 4)?)?)?);
+            }
+        }
+
+        let directory_range: Range<u32> = directory.rva..add_rva(directory.rva, directory.size as usize)?;
+        let mut exports: Vec<Export> = Vec::new();
+
+        for (idx, name) in function_names.into_iter().enumerate() {
+            let rva: u32 = self.read_u32(add_rva(functions, idx * 4)?)?;
+
+            if rva == 0 {
+                continue;
+            }
+
+            let forwarder: Option<String> = match directory_range.contains(&rva) {
+                true => Some(self.read_name(rva)?),
+                false => None
+            };
+
+            let ordinal: u32 = add_rva(base, idx).map_err(|_| PeError::TooManyEntries { rva: directory.rva })?;
+
+            exports.push(Export { name: name, ordinal: ordinal, rva: rva, forwarder: forwarder });
+        }
+
+        return Ok(exports);
+    }
+
+    /**
+     * The export with this name, case sensitive like GetProcAddress
+     */
+    pub fn export(&self, name: &str) -> Result<Option<Export>, PeError> {
+        return Ok(self.exports()?.into_iter().find(|export| export.name.as_deref() == Some(name)));
+    }
+
+    /**
+     * Every imported module with the functions taken from it
+     * Names come from the import lookup table, in a mapped module the IAT already holds addresses, so modules without a lookup table list no functions there
+     */
+    pub fn imports(&self) -> Result<Vec<Import>, PeError> {
+        let directory: DataDirectory = match self.directory(IMAGE_DIRECTORY_ENTRY_IMPORT) {
+            Some(directory) => directory,
+            None => return Ok(Vec::new())
+        };
+
+        let mut imports: Vec<Import> = Vec::new();
+
+        for idx in 0..MAX_IMPORT_DESCRIPTORS {
+            let descriptor: u32 = add_rva(directory.rva, idx * IMPORT_DESCRIPTOR_SIZE)?;
+            let original_first_thunk: u32 = self.read_u32(add_rva(descriptor, IMPORT_ORIGINAL_FIRST_THUNK_OFFSET)?)?;
+            let name: u32 = self.read_u32(add_rva(descriptor, IMPORT_NAME_OFFSET)?)?;
+            let first_thunk: u32 = self.read_u32(add_rva(descriptor, IMPORT_FIRST_THUNK_OFFSET)?)?;
+
+            if name == 0 && first_thunk == 0 {
+                return Ok(imports);
+            }
+
+            let lookup_table: u32 = match original_first_thunk {
+                0 if self.layout == PeLayout::Mapped => 0,
+                0 => first_thunk,
+                _ => original_first_thunk
+            };
+
+            imports.push(Import {
+                module: self.read_name(name)?,
+                functions: if lookup_table == 0 { Vec::new() } else { self.imported_functions(lookup_table, first_thunk)? }
+            });
+        }
+
+        return Err(PeError::TooManyEntries { rva: directory.rva });
+    }
+
+    fn imported_functions(&self, lookup_table: u32, first_thunk: u32) -> Result<Vec<ImportedFunction>, PeError> {
+        let mut functions: Vec<ImportedFunction> = Vec::new();
+
+        for idx in 0..MAX_THUNKS {
+            let thunk: u64 = self.read_u64(add_rva(lookup_table, idx * THUNK_SIZE)?)?;
+
+            if thunk == 0 {
+                return Ok(functions);
+            }
+
+            let name: ImportName = match thunk & IMAGE_ORDINAL_FLAG64 {
+                0 => ImportName::Name { hint: self.read_u16(thunk as u32)?, name: self.read_name(add_rva(thunk as u32, 2)?)? },
+                _ => ImportName::Ordinal(thunk as u16)
+            };
+
+            functions.push(ImportedFunction { name: name, iat_rva: add_rva(first_thunk, idx * THUNK_SIZE)? });
+        }
+
+        return Err(PeError::TooManyEntries { rva: lookup_table });
+    }
+}
+
+/**
+ * Copies the first page of a mapped module, enough for the headers and section table
+ */
+pub fn read_mapped_headers(mem: &dyn MemoryReader, base: usize) -> ReflectionResult<Vec<u8>> {
+    let mut headers: Vec<u8> = vec![0u8; PAGE_SIZE];
+
+    if !mem.read_bytes(base, &mut headers) {
+        return Err(ReflectionError::Unreadable { address: base, len: PAGE_SIZE });
+    }
+
+    return Ok(headers);
+}
+
+/**
+ * Copies SizeOfImage bytes of a mapped module, pages that can not be read are left zeroed
+ */
+pub fn read_mapped_image(mem: &dyn MemoryReader, base: usize) -> ReflectionResult<Vec<u8>> {
+    let headers: Vec<u8> = read_mapped_headers(mem, base)?;
+    let size_of_image: usize = PeImage::parse(&headers, PeLayout::Mapped).map_err(|_| ReflectionError::InvalidImage { address: base })?.size_of_image as usize;
+
+    let mut image: Vec<u8> = vec![0u8; size_of_image];
+
+    for (idx, page) in image.chunks_mut(PAGE_SIZE).enumerate() {
+        if !base.checked_add(idx * PAGE_SIZE).is_some_and(|address| mem.read_bytes(address, page)) {
+            page.fill(0);
+        }
+    }
+
+    return Ok(image);
+}
+
+/**
+ * Offsets and RVAs come straight out of the image, so every sum is checked, a corrupt header is an error rather than a panic
+ */
+fn add_offset(offset: usize, field: usize) -> Result<usize, PeError> {
+    return offset.checked_add(field).ok_or(PeError::Truncated { offset: offset });
+}
+
+fn add_rva(rva: u32, field: usize) -> Result<u32, PeError> {
+    return u32::try_from(field).ok().and_then(|field| rva.checked_add(field)).ok_or(PeError::UnmappedRva { rva: rva });
+}
+
+fn read_array<const N: usize>(bytes: &[u8], offset: usize) -> Result<[u8; N], PeError> {
+    let end: usize = add_offset(offset, N)?;
+
+    return bytes.get(offset..end).and_then(|bytes| bytes.try_into().ok()).ok_or(PeError::Truncated { offset: offset });
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16, PeError> {
+    return Ok(u16::from_le_bytes(read_array(bytes, offset)?));
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, PeError> {
+    return Ok(u32::from_le_bytes(read_array(bytes, offset)?));
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Result<u64, PeError> {
+    return Ok(u64::from_le_bytes(read_array(bytes, offset)?));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SnapshotMemory;
+
+    fn fixture(name: &str) -> Vec<u8> {
+        return std::fs::read(std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(name)).unwrap();
+    }
+
+    fn section_names<'a>(pe: &'a PeImage) -> Vec<&'a str> {
+        return pe.sections.iter().map(|section| section.name.as_str()).collect();
+    }
+
+    fn import_summary(pe: &PeImage) -> Vec<(String, usize)> {
+        return pe.imports().unwrap().into_iter().map(|import| (import.module, import.functions.len())).collect();
+    }
+
+    /**
+     * Lays a file out the way the loader would, headers and every section's raw data at its RVA
+     */
+    fn map(file: &[u8]) -> Vec<u8> {
+        let pe: PeImage = PeImage::parse(file, PeLayout::File).unwrap();
+        let mut image: Vec<u8> = vec![0u8; pe.size_of_image as usize];
+
+        image[..pe.size_of_headers as usize].copy_from_slice(&file[..pe.size_of_headers as usize]);
+
+        for section in pe.sections.iter() {
+            let len: usize = section.raw_size.min(section.virtual_size) as usize;
+            let rva: usize = section.virtual_address as usize;
+            let raw: usize = section.raw_offset as usize;
+
+            image[rva..rva + len].copy_from_slice(&file[raw..raw + len]);
+        }
+
+        return image;
+    }
+
+    #[test]
+    fn injector_headers_and_sections() {
+        let file: Vec<u8> = fixture("Injector.exe");
+        let pe: PeImage = PeImage::parse(&file, PeLayout::File).unwrap();
+
+        assert_eq!(pe.machine, 0x8664);
+        assert_eq!(pe.timestamp, 0x64d8d55c);
+        assert_eq!(pe.entry_point, 0x39d80);
+        assert_eq!(pe.image_base, 0x140000000);
+        assert_eq!(pe.size_of_image, 0x96000);
+        assert_eq!(section_names(&pe), vec![".text", ".rdata", ".data", ".pdata", "_RDATA", ".rsrc", ".reloc"]);
+        assert_eq!(pe.code_sections(), vec![0x1000..0x6a448]);
+        assert_eq!(pe.section_of(0x6b020).unwrap().name, ".rdata");
+        assert_eq!(pe.rva_to_offset(0x6b020), Ok(0x69a20));
+        assert_eq!(pe.rva_to_offset(0x89000), Err(PeError::UnmappedRva { rva: 0x89000 }));
+    }
+
+    #[test]
+    fn injector_imports_without_exports() {
+        let file: Vec<u8> = fixture("Injector.exe");
+        let pe: PeImage = PeImage::parse(&file, PeLayout::File).unwrap();
+        let kernel32: Import = pe.imports().unwrap().into_iter().find(|import| import.module == "KERNEL32.dll").unwrap();
+
+        assert_eq!(import_summary(&pe), vec![("SHLWAPI.dll".to_string(), 1), ("KERNEL32.dll".to_string(), 102), ("USER32.dll".to_string(), 2), ("ADVAPI32.dll".to_string(), 3)]);
+        assert!(matches!(&kernel32.functions[0].name, ImportName::Name { name, .. } if name == "WriteProcessMemory"));
+        assert_eq!(kernel32.functions[0].iat_rva, 0x6b020);
+        assert_eq!(pe.exports(), Ok(Vec::new()));
+        assert_eq!(pe.export_name(), Ok(None));
+    }
+
+    #[test]
+    fn reborn_dll_exports_and_imports() {
+        let file: Vec<u8> = fixture("ReBorn.dll");
+        let pe: PeImage = PeImage::parse(&file, PeLayout::File).unwrap();
+        let dll_main: Export = Export { name: Some("DllMain".to_string()), ordinal: 1, rva: 0x74f0, forwarder: None };
+
+        assert_eq!(pe.timestamp, 0x6555a57d);
+        assert_eq!(pe.image_base, 0x180000000);
+        assert_eq!(section_names(&pe), vec![".text", ".rdata", ".data", ".pdata", ".reloc"]);
+        assert_eq!(pe.code_sections(), vec![0x1000..0x39f57]);
+        assert_eq!(pe.export_name(), Ok(Some("ReBorn.dll".to_string())));
+        assert_eq!(pe.exports(), Ok(vec![dll_main.clone()]));
+        assert_eq!(pe.export("DllMain"), Ok(Some(dll_main)));
+        assert_eq!(pe.export("dllmain"), Ok(None));
+        assert_eq!(import_summary(&pe), vec![
+            ("KERNEL32.dll".to_string(), 70),
+            ("PSAPI.DLL".to_string(), 1),
+            ("USER32.dll".to_string(), 1),
+            ("ntdll.dll".to_string(), 2),
+            ("VCRUNTIME140.dll".to_string(), 8),
+            ("api-ms-win-crt-runtime-l1-1-0.dll".to_string(), 8),
+            ("api-ms-win-crt-heap-l1-1-0.dll".to_string(), 1)
+        ]);
+    }
+
+    #[test]
+    fn mapped_modules_read_like_their_files() {
+        let file: Vec<u8> = fixture("ReBorn.dll");
+        let base: usize = 0x180000000;
+        let mut mem: SnapshotMemory = SnapshotMemory::new();
+        mem.add_region(base, map(&file));
+
+        let image: Vec<u8> = read_mapped_image(&mem, base).unwrap();
+        let mapped: PeImage = PeImage::parse(&image, PeLayout::Mapped).unwrap();
+        let on_disk: PeImage = PeImage::parse(&file, PeLayout::File).unwrap();
+
+        assert_eq!(image.len(), 0x52000);
+        assert_eq!(mapped.timestamp, on_disk.timestamp);
+        assert_eq!(mapped.sections, on_disk.sections);
+        assert_eq!(mapped.exports(), on_disk.exports());
+        assert_eq!(mapped.imports(), on_disk.imports());
+    }
+
+    #[test]
+    fn corrupt_headers_are_errors() {
+        let file: Vec<u8> = fixture("ReBorn.dll");
+        let nt_headers: usize = u32::from_le_bytes(file[DOS_E_LFANEW_OFFSET..DOS_E_LFANEW_OFFSET + 4].try_into().unwrap()) as usize;
+        let text: usize = nt_headers + NT_OPTIONAL_HEADER_OFFSET + 0xF0;
+
+        let mut far_headers: Vec<u8> = file.clone();
+        far_headers[DOS_E_LFANEW_OFFSET..DOS_E_LFANEW_OFFSET + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        assert_eq!(PeImage::parse(&far_headers, PeLayout::File).err(), Some(PeError::Truncated { offset: u32::MAX as usize }));
+        assert_eq!(PeImage::parse(&file[..0x100], PeLayout::File).err(), Some(PeError::Truncated { offset: nt_headers + NT_OPTIONAL_HEADER_OFFSET }));
+        assert_eq!(PeImage::parse(b"MZ", PeLayout::File).err(), Some(PeError::Truncated { offset: DOS_E_LFANEW_OFFSET }));
+        assert_eq!(PeImage::parse(b"not a PE", PeLayout::File).err(), Some(PeError::InvalidSignature));
+
+        let mut wrapping_section: Vec<u8> = file.clone();
+        wrapping_section[text + SECTION_VIRTUAL_ADDRESS_OFFSET..text + SECTION_VIRTUAL_ADDRESS_OFFSET + 4].copy_from_slice(&0xFFFFF000u32.to_le_bytes());
+        wrapping_section[text + SECTION_POINTER_TO_RAW_DATA_OFFSET..text + SECTION_POINTER_TO_RAW_DATA_OFFSET + 4].copy_from_slice(&0xFFFFFF00u32.to_le_bytes());
+
+        let pe: PeImage = PeImage::parse(&wrapping_section, PeLayout::File).unwrap();
+
+        assert_eq!(pe.code_sections(), vec![0xFFFFF000..0xFFFFF000 + 0x38f57]);
+        assert_eq!(pe.rva_to_offset(0xFFFFFFF0), Err(PeError::UnmappedRva { rva: 0xFFFFFFF0 }));
+        assert_eq!(pe.read_u32(0xFFFFF000), Err(PeError::Truncated { offset: 0xFFFFFF00 }));
+    }
+}
@@ -0,0 +1,349 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::error::{ReflectionError, ReflectionResult};
+use crate::fields::FieldLayout;
+use crate::malloc::FMallocSlots;
+use crate::memory::MemoryReader;
+use crate::pe::{PeImage, PeLayout, read_mapped_headers};
+use crate::reflection::UObjectLayout;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/**
+ * Offsets for one build of Battleborn.exe, as written in profiles.json
+ * Numbers are hex strings like in config.json, e.g. "0x3515230"
+ * A profile is picked by timestamp (the PE link timestamp) and/or hash (FNV-1a 64 of the executable file), every one that is given has to match
+ * A profile with neither never matches, it is only used when config.json names it in profile
+ */
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OffsetProfile {
+    pub name: String,
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    #[serde(default)]
+    pub hash: Option<String>,
+    pub gnames: String,
+    pub gobjects: String,
+    pub process_event: String,
+    pub static_construct_object: String,
+    pub engine_process_command: String,
+    /**
+     * Distance from the first GObjects entry to the engine object console commands are executed on
+     */
+    pub engine_delta: String,
+    #[serde(default)]
+    pub layout: LayoutProfile
+}
+
+/**
+ * UObject field offsets, then the UField, UStruct, UFunction, UProperty and UEnum ones (property_size being sizeof(UProperty))
+ * The fmalloc ones are vtable slots of GMalloc's Malloc, Realloc and Free rather than byte offsets
+ * Anything left out keeps the offset of the original build
+ */
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutProfile {
+    #[serde(default)]
+    pub object_flags: Option<String>,
+    #[serde(default)]
+    pub index: Option<String>,
+    #[serde(default)]
+    pub outer: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub field_next: Option<String>,
+    #[serde(default)]
+    pub struct_super_field: Option<String>,
+    #[serde(default)]
+    pub struct_children: Option<String>,
+    #[serde(default)]
+    pub struct_property_size: Option<String>,
+    #[serde(default)]
+    pub function_parms_size: Option<String>,
+    #[serde(default)]
+    pub property_array_dim: Option<String>,
+    #[serde(default)]
+    pub property_element_size: Option<String>,
+    #[serde(default)]
+    pub property_flags: Option<String>,
+    #[serde(default)]
+    pub property_offset: Option<String>,
+    #[serde(default)]
+    pub property_size: Option<String>,
+    #[serde(default)]
+    pub enum_names: Option<String>,
+    #[serde(default)]
+    pub fmalloc_malloc: Option<String>,
+    #[serde(default)]
+    pub fmalloc_realloc: Option<String>,
+    #[serde(default)]
+    pub fmalloc_free: Option<String>
+}
+
+/**
+ * A profile with every number parsed, module offsets are RVAs
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Offsets {
+    pub gnames: usize,
+    pub gobjects: usize,
+    pub process_event: usize,
+    pub static_construct_object: usize,
+    pub engine_process_command: usize,
+    pub engine_delta: usize,
+    pub layout: UObjectLayout,
+    pub fields: FieldLayout,
+    pub fmalloc: FMallocSlots
+}
+
+/**
+ * What identifies the running executable
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExecutableIdentity {
+    pub timestamp: u32,
+    /**
+     * None when the executable file could not be read, profiles that need a hash then never match
+     */
+    pub hash: Option<u64>
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProfileError {
+    Unreadable(String),
+    Malformed(String),
+    InvalidNumber { profile: String, field: String, value: String },
+    NoMatch(ExecutableIdentity),
+    NotFound(String)
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            ProfileError::Unreadable(error) => write!(f, "unable to read profiles: {}", error),
+            ProfileError::Malformed(error) => write!(f, "profiles are malformed: {}", error),
+            ProfileError::InvalidNumber { profile, field, value } => write!(f, "{} in profile {} is not a hex number: {}", field, profile, value),
+            ProfileError::NoMatch(identity) => write!(f, "no profile matches timestamp {:08x} hash {}", identity.timestamp, identity.hash.map_or("unknown".to_string(), |hash| format!("{:016x}", hash))),
+            ProfileError::NotFound(name) => write!(f, "no profile named {}", name)
+        };
+    }
+}
+
+impl OffsetProfile {
+    pub fn is_unidentified(&self) -> bool {
+        return self.timestamp.is_none() && self.hash.is_none();
+    }
+
+    /**
+     * An unidentified profile never matches, it could be for any build
+     */
+    pub fn matches(&self, identity: &ExecutableIdentity) -> bool {
+        if self.is_unidentified() {
+            return false;
+        }
+
+        if let Some(timestamp) = &self.timestamp {
+            if parse_hex(timestamp) != Some(identity.timestamp as u64) {
+                return false;
+            }
+        }
+
+        if let Some(hash) = &self.hash {
+            if identity.hash.is_none() || parse_hex(hash) != identity.hash {
+                return false;
+            }
+        }
+
+        return true;
+    }
+
+    pub fn offsets(&self) -> Result<Offsets, ProfileError> {
+        let default_layout: UObjectLayout = UObjectLayout::default();
+        let default_fields: FieldLayout = FieldLayout::default();
+        let default_fmalloc: FMallocSlots = FMallocSlots::default();
+
+        return Ok(Offsets {
+            gnames: self.number("gnames", &self.gnames)?,
+            gobjects: self.number("gobjects", &self.gobjects)?,
+            process_event: self.number("processEvent", &self.process_event)?,
+            static_construct_object: self.number("staticConstructObject", &self.static_construct_object)?,
+            engine_process_command: self.number("engineProcessCommand", &self.engine_process_command)?,
+            engine_delta: self.number("engineDelta", &self.engine_delta)?,
+            layout: UObjectLayout {
+                object_flags: self.layout_number("objectFlags", &self.layout.object_flags, default_layout.object_flags)?,
+                index: self.layout_number("index", &self.layout.index, default_layout.index)?,
+                outer: self.layout_number("outer", &self.layout.outer, default_layout.outer)?,
+                name: self.layout_number("name", &self.layout.name, default_layout.name)?,
+                class: self.layout_number("class", &self.layout.class, default_layout.class)?
+            },
+            fields: FieldLayout {
+                field_next: self.layout_number("fieldNext", &self.layout.field_next, default_fields.field_next)?,
+                struct_super_field: self.layout_number("structSuperField", &self.layout.struct_super_field, default_fields.struct_super_field)?,
+                struct_children: self.layout_number("structChildren", &self.layout.struct_children, default_fields.struct_children)?,
+                struct_property_size: self.layout_number("structPropertySize", &self.layout.struct_property_size, default_fields.struct_property_size)?,
+                function_parms_size: self.layout_number("functionParmsSize", &self.layout.function_parms_size, default_fields.function_parms_size)?,
+                property_array_dim: self.layout_number("propertyArrayDim", &self.layout.property_array_dim, default_fields.property_array_dim)?,
+                property_element_size: self.layout_number("propertyElementSize", &self.layout.property_element_size, default_fields.property_element_size)?,
+                property_flags: self.layout_number("propertyFlags", &self.layout.property_flags, default_fields.property_flags)?,
+                property_offset: self.layout_number("propertyOffset", &self.layout.property_offset, default_fields.property_offset)?,
+                property_size: self.layout_number("propertySize", &self.layout.property_size, default_fields.property_size)?,
+                enum_names: self.layout_number("enumNames", &self.layout.enum_names, default_fields.enum_names)?
+            },
+            fmalloc: FMallocSlots {
+                malloc: self.layout_number("fmallocMalloc", &self.layout.fmalloc_malloc, default_fmalloc.malloc)?,
+                realloc: self.layout_number("fmallocRealloc", &self.layout.fmalloc_realloc, default_fmalloc.realloc)?,
+                free: self.layout_number("fmallocFree", &self.layout.fmalloc_free, default_fmalloc.free)?
+            }
+        });
+    }
+
+    fn number(&self, field: &str, value: &str) -> Result<usize, ProfileError> {
+        return parse_hex(value).map(|value| value as usize).ok_or_else(|| ProfileError::InvalidNumber { profile: self.name.clone(), field: field.to_string(), value: value.to_string() });
+    }
+
+    fn layout_number(&self, field: &str, value: &Option<String>, default: usize) -> Result<usize, ProfileError> {
+        return match value {
+            Some(value) => self.number(field, value),
+            None => Ok(default)
+        };
+    }
+}
+
+impl ExecutableIdentity {
+    /**
+     * Identity of a module mapped at base, hashing the file it was loaded from
+     * The link timestamp is the one thing every build of the executable is guaranteed to differ in
+     */
+    pub fn read(mem: &dyn MemoryReader, base: usize, path: &Path) -> ReflectionResult<ExecutableIdentity> {
+        let headers: Vec<u8> = read_mapped_headers(mem, base)?;
+        let pe: PeImage = PeImage::parse(&headers, PeLayout::Mapped).map_err(|_| ReflectionError::InvalidImage { address: base })?;
+
+        return Ok(ExecutableIdentity {
+            timestamp: pe.timestamp,
+            hash: fs::read(path).ok().map(|bytes| fnv1a64(&bytes))
+        });
+    }
+}
+
+/**
+ * Reads the JSON array of profiles
+ */
+pub fn load_profiles(path: &Path) -> Result<Vec<OffsetProfile>, ProfileError> {
+    let text: String = fs::read_to_string(path).map_err(|error| ProfileError::Unreadable(error.to_string()))?;
+
+    return serde_json::from_str(&text).map_err(|error| ProfileError::Malformed(error.to_string()));
+}
+
+/**
+ * The profile named forced_name if there is one, otherwise the first profile matching identity
+ * Nothing is guessed when none matches, hooking with another build's offsets would write to random memory
+ */
+pub fn select_profile<'a>(profiles: &'a [OffsetProfile], identity: &ExecutableIdentity, forced_name: Option<&str>) -> Result<&'a OffsetProfile, ProfileError> {
+    if let Some(forced_name) = forced_name {
+        return profiles.iter().find(|profile| profile.name == forced_name).ok_or_else(|| ProfileError::NotFound(forced_name.to_string()));
+    }
+
+    return profiles.iter().find(|profile| profile.matches(identity)).ok_or(ProfileError::NoMatch(*identity));
+}
+
+/**
+ * FNV-1a 64, enough to tell builds apart without pulling in a hashing crate
+ */
+pub fn fnv1a64(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = FNV_OFFSET_BASIS;
+
+    for byte in bytes {
+        hash = (hash ^ *byte as u64).wrapping_mul(FNV_PRIME);
+    }
+
+    return hash;
+}
+
+fn parse_hex(text: &str) -> Option<u64> {
+    let text: &str = text.trim();
+
+    return u64::from_str_radix(text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")).unwrap_or(text), 16).ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str, timestamp: Option<&str>, gnames: usize, gobjects: usize) -> OffsetProfile {
+        return OffsetProfile {
+            name: name.to_string(),
+            timestamp: timestamp.map(|timestamp| timestamp.to_string()),
+            hash: None,
+            gnames: format!("{:#x}", gnames),
+            gobjects: format!("{:#x}", gobjects),
+            process_event: "0x0".to_string(),
+            static_construct_object: "0x0".to_string(),
+            engine_process_command: "0x0".to_string(),
+            engine_delta: "0x0".to_string(),
+            layout: LayoutProfile::default()
+        };
+    }
+
+    #[test]
+    fn profiles_match_by_timestamp() {
+        let identity: ExecutableIdentity = ExecutableIdentity { timestamp: 0x5a000000, hash: None };
+        let profiles: Vec<OffsetProfile> = vec![
+            profile("other build", Some("0x5b000000"), 0x10, 0x20),
+            profile("exact", Some("0x5a000000"), 0x10, 0x20)
+        ];
+
+        assert_eq!(select_profile(&profiles, &identity, None).unwrap().name, "exact");
+        assert_eq!(select_profile(&profiles, &identity, Some("other build")).unwrap().name, "other build");
+        assert_eq!(select_profile(&profiles, &identity, Some("missing")).unwrap_err(), ProfileError::NotFound("missing".to_string()));
+        assert_eq!(select_profile(&profiles[..1], &identity, None).unwrap_err(), ProfileError::NoMatch(identity));
+    }
+
+    #[test]
+    fn unidentified_profiles_are_only_used_when_named() {
+        let identity: ExecutableIdentity = ExecutableIdentity { timestamp: 0x5a000000, hash: Some(0x1234) };
+        let mut profiles: Vec<OffsetProfile> = vec![profile("original", None, 0x10, 0x20)];
+
+        assert!(profiles[0].is_unidentified());
+        assert!(!profiles[0].matches(&identity));
+        assert_eq!(select_profile(&profiles, &identity, None).unwrap_err(), ProfileError::NoMatch(identity));
+        assert_eq!(select_profile(&profiles, &identity, Some("original")).unwrap().name, "original");
+
+        profiles[0].hash = Some("0x1234".to_string());
+
+        assert_eq!(select_profile(&profiles, &identity, None).unwrap().name, "original");
+    }
+
+    #[test]
+    fn layouts_default_to_the_original_build() {
+        let mut profile: OffsetProfile = profile("moved", Some("0x1"), 0x10, 0x20);
+        profile.layout.outer = Some("0x40".to_string());
+
+        let offsets: Offsets = profile.offsets().unwrap();
+
+        assert_eq!(offsets.layout.outer, 0x40);
+        assert_eq!(offsets.layout.class, UObjectLayout::default().class);
+        assert_eq!(offsets.fields, FieldLayout::default());
+        assert_eq!(offsets.fmalloc, FMallocSlots::default());
+
+        profile.layout.property_size = Some("0xD0".to_string());
+
+        assert_eq!(profile.offsets().unwrap().fields.property_size, 0xD0);
+
+        profile.layout.fmalloc_realloc = Some("0x4".to_string());
+
+        assert_eq!(profile.offsets().unwrap().fmalloc, FMallocSlots { malloc: 1, realloc: 4, free: 3 });
+
+        profile.layout.class = Some("0xZZ".to_string());
+
+        assert_eq!(profile.offsets().unwrap_err(), ProfileError::InvalidNumber { profile: "moved".to_string(), field: "class".to_string(), value: "0xZZ".to_string() });
+    }
+}
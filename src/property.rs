@@ -0,0 +1,757 @@
+use std::fmt;
+
+use crate::error::{ReflectionError, ReflectionResult};
+use crate::fields::{PropertyInfo, UEnumRef, UPropertyRef, UStructRef, field_layout, is_a, is_property_class};
+use crate::fstring::{FString, fstring_units};
+use crate::malloc::{DEFAULT_ALIGNMENT, EngineAllocator};
+use crate::memory::{MemoryReader, MemoryWriter};
+use crate::names::FName;
+use crate::reflection::{TARRAY_DATA_OFFSET, TARRAY_MAX_OFFSET, TARRAY_NUM_OFFSET, TArray, UObjectRef};
+
+/**
+ * Offsets of the fields UProperty subclasses add from the end of UProperty (FieldLayout::property_size)
+ */
+const UBOOLPROPERTY_BITMASK_OFFSET: usize = 0x0;
+const UBYTEPROPERTY_ENUM_OFFSET: usize = 0x0;
+const UOBJECTPROPERTY_PROPERTYCLASS_OFFSET: usize = 0x0;
+const UCLASSPROPERTY_METACLASS_OFFSET: usize = 0x8;
+const USTRUCTPROPERTY_STRUCT_OFFSET: usize = 0x0;
+const UARRAYPROPERTY_INNER_OFFSET: usize = 0x0;
+
+/**
+ * Structs nest, but never this deep
+ */
+const MAX_STRUCT_DEPTH: usize = 16;
+
+/**
+ * A property value decoded according to its UProperty class
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub enum PropertyValue {
+    Int(i32),
+    Float(f32),
+    Bool(bool),
+    Byte(u8),
+    /**
+     * A ByteProperty bound to a UEnum, the value and its name
+     * When written the name is what counts, the value is only used if the name is empty
+     */
+    Enum(u8, String),
+    Name(FName, String),
+    Str(String),
+    Object(Option<UObjectRef>),
+    Class(Option<UObjectRef>),
+    Array(Vec<PropertyValue>),
+    Struct(Vec<(String, PropertyValue)>),
+    /**
+     * Delegates, maps and anything else without a decoder, the raw bytes are kept
+     */
+    Unknown { class_name: String, bytes: Vec<u8> }
+}
+
+impl fmt::Display for PropertyValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            PropertyValue::Int(value) => write!(f, "{}", value),
+            PropertyValue::Float(value) => write!(f, "{}", value),
+            PropertyValue::Bool(value) => write!(f, "{}", value),
+            PropertyValue::Byte(value) => write!(f, "{}", value),
+            PropertyValue::Enum(value, name) if name.is_empty() => write!(f, "{}", value),
+            PropertyValue::Enum(_, name) => write!(f, "{}", name),
+            PropertyValue::Name(_, name) => write!(f, "'{}'", name),
+            PropertyValue::Str(value) => write!(f, "\"{}\"", value),
+            PropertyValue::Object(Some(object)) | PropertyValue::Class(Some(object)) => write!(f, "{:x}", object.address()),
+            PropertyValue::Object(None) | PropertyValue::Class(None) => write!(f, "None"),
+            PropertyValue::Array(values) => {
+                write!(f, "(")?;
+                for (idx, value) in values.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, ")")
+            }
+            PropertyValue::Struct(fields) => {
+                write!(f, "(")?;
+                for (idx, (name, value)) in fields.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}={}", name, value)?;
+                }
+                write!(f, ")")
+            }
+            PropertyValue::Unknown { class_name, bytes } => write!(f, "<{} {} bytes>", class_name, bytes.len())
+        };
+    }
+}
+
+/**
+ * Finds a property by name on the object's class (or any super class)
+ */
+pub fn find_property(mem: &dyn MemoryReader, gnames: TArray, object: UObjectRef, name: &str) -> ReflectionResult<PropertyInfo> {
+    return find_struct_property(mem, gnames, UStructRef(object.class(mem)?), name);
+}
+
+/**
+ * Finds a property by name on a UStruct, used for objects (through their class), script structs and function params alike
+ */
+pub fn find_struct_property(mem: &dyn MemoryReader, gnames: TArray, ustruct: UStructRef, name: &str) -> ReflectionResult<PropertyInfo> {
+    let field: UObjectRef = match ustruct.find_field(mem, gnames, name)? {
+        Some(field) => field,
+        None => return Err(ReflectionError::PropertyNotFound { name: name.to_string() })
+    };
+
+    if !is_property_class(&field.class_name(mem, gnames)?) {
+        return Err(ReflectionError::PropertyNotFound { name: name.to_string() });
+    }
+
+    return PropertyInfo::read(mem, gnames, UPropertyRef(field));
+}
+
+/**
+ * Reads a property of a live object by name, e.g. read_property(mem, gnames, player_controller, "FOVAngle")
+ */
+pub fn read_property(mem: &dyn MemoryReader, gnames: TArray, object: UObjectRef, name: &str) -> ReflectionResult<PropertyValue> {
+    let property: PropertyInfo = find_property(mem, gnames, object, name)?;
+
+    return read_value(mem, gnames, &property, object.address());
+}
+
+/**
+ * Reads a property out of a container (object, struct or params buffer) starting at base
+ * Static arrays (ArrayDim above 1) come back as an Array of every element
+ */
+pub fn read_value(mem: &dyn MemoryReader, gnames: TArray, property: &PropertyInfo, base: usize) -> ReflectionResult<PropertyValue> {
+    return read_value_at_depth(mem, gnames, property, base, 0);
+}
+
+fn read_value_at_depth(mem: &dyn MemoryReader, gnames: TArray, property: &PropertyInfo, base: usize, depth: usize) -> ReflectionResult<PropertyValue> {
+    let address: usize = base + property.offset;
+
+    if property.array_dim <= 1 {
+        return read_element(mem, gnames, property.property, &property.class_name, property.element_size, address, depth);
+    }
+
+    let mut values: Vec<PropertyValue> = Vec::new();
+
+    for idx in 0..property.array_dim {
+        values.push(read_element(mem, gnames, property.property, &property.class_name, property.element_size, address + (idx * property.element_size), depth)?);
+    }
+
+    return Ok(PropertyValue::Array(values));
+}
+
+/**
+ * Decodes a single element of a property that lives at address
+ */
+fn read_element(mem: &dyn MemoryReader, gnames: TArray, property: UPropertyRef, class_name: &str, element_size: usize, address: usize, depth: usize) -> ReflectionResult<PropertyValue> {
+    if depth > MAX_STRUCT_DEPTH {
+        return Err(ReflectionError::ChainTooLong { address: property.0.address() });
+    }
+
+    return match class_name {
+        "IntProperty" => Ok(PropertyValue::Int(mem.read_i32(address)?)),
+        "FloatProperty" => Ok(PropertyValue::Float(mem.read_f32(address)?)),
+        "BoolProperty" => {
+            let mask: u32 = bool_property_mask(mem, property)?;
+            Ok(PropertyValue::Bool(mem.read_u32(address)? & mask != 0))
+        }
+        "ByteProperty" => {
+            let value: u8 = mem.read_u8(address)?;
+
+            // Values past the end of the enum (corrupt or not yet initialized) stay plain bytes
+            let name: Option<String> = match byte_property_enum(mem, property)? {
+                Some(uenum) => uenum.name_of(mem, gnames, value)?,
+                None => None
+            };
+
+            match name {
+                Some(name) => Ok(PropertyValue::Enum(value, name)),
+                None => Ok(PropertyValue::Byte(value))
+            }
+        }
+        "NameProperty" => {
+            let fname: FName = FName::read(mem, address)?;
+            Ok(PropertyValue::Name(fname, fname.resolve(mem, gnames)?))
+        }
+        "StrProperty" => Ok(PropertyValue::Str(FString::new(address).read_string(mem)?)),
+        "ObjectProperty" | "ComponentProperty" | "InterfaceProperty" => Ok(PropertyValue::Object(read_optional_object(mem, address)?)),
+        "ClassProperty" => Ok(PropertyValue::Class(read_optional_object(mem, address)?)),
+        "StructProperty" => {
+            let ustruct: UStructRef = struct_property_struct(mem, property)?;
+            let mut fields: Vec<(String, PropertyValue)> = Vec::new();
+
+            for field in ustruct.properties(mem, gnames)? {
+                let value: PropertyValue = read_value_at_depth(mem, gnames, &field, address, depth + 1)?;
+                fields.push((field.name, value));
+            }
+
+            Ok(PropertyValue::Struct(fields))
+        }
+        "ArrayProperty" => {
+            let inner: PropertyInfo = PropertyInfo::read(mem, gnames, array_inner(mem, property)?)?;
+            // Element size is only known at runtime, so the array is untyped and only its header is read
+            let elements: TArray<()> = TArray::new(address);
+            let mut values: Vec<PropertyValue> = Vec::new();
+
+            for idx in 0..elements.len(mem)? {
+                values.push(read_element(mem, gnames, inner.property, &inner.class_name, inner.element_size, elements.element_address(mem, idx, inner.element_size)?, depth + 1)?);
+            }
+
+            Ok(PropertyValue::Array(values))
+        }
+        _ => {
+            let mut bytes: Vec<u8> = vec![0u8; element_size];
+            mem.read_exact(address, &mut bytes)?;
+            Ok(PropertyValue::Unknown { class_name: class_name.to_string(), bytes: bytes })
+        }
+    };
+}
+
+/**
+ * A single pending change to game memory, the writer collects all of them before touching anything
+ * Bools are kept as bit operations so several bitfield members sharing a word can be set in the same write
+ */
+enum Patch {
+    Bytes { address: usize, bytes: Vec<u8> },
+    Bits { address: usize, mask: u32, set: bool }
+}
+
+impl Patch {
+    /**
+     * The bytes the patch overwrites
+     */
+    fn range(&self) -> (usize, usize) {
+        return match self {
+            Patch::Bytes { address, bytes } => (*address, bytes.len()),
+            Patch::Bits { address, .. } => (*address, 4)
+        };
+    }
+
+    fn apply(&self, mem: &mut dyn MemoryWriter) -> ReflectionResult<()> {
+        return match self {
+            Patch::Bytes { address, bytes } => mem.write_exact(*address, bytes),
+            Patch::Bits { address, mask, set } => {
+                let word: u32 = mem.read_u32(*address)?;
+                mem.write_u32(*address, if *set { word | mask } else { word & !mask })
+            }
+        };
+    }
+}
+
+/**
+ * Applies patches in order as one change
+ * Every target is read before the first write, so an unreadable address fails with nothing written
+ * If a write fails anyway (read only pages), everything written up to it is put back the way it was
+ */
+fn apply_patches(mem: &mut dyn MemoryWriter, patches: &[Patch]) -> ReflectionResult<()> {
+    let mut originals: Vec<(usize, Vec<u8>)> = Vec::with_capacity(patches.len());
+
+    for patch in patches.iter() {
+        let (address, len) = patch.range();
+        let mut bytes: Vec<u8> = vec![0u8; len];
+        mem.read_exact(address, &mut bytes)?;
+        originals.push((address, bytes));
+    }
+
+    for (idx, patch) in patches.iter().enumerate() {
+        if let Err(error) = patch.apply(mem) {
+            for (address, bytes) in originals[..=idx].iter().rev() {
+                let _ = mem.write_bytes(*address, bytes);
+            }
+            return Err(error);
+        }
+    }
+
+    return Ok(());
+}
+
+/**
+ * Patches collected for one write, plus the engine allocations made for strings and arrays that had to grow
+ * New buffers are allocated while encoding so later patches can point at them, they are freed again if encoding fails
+ * The buffers they replace are only freed once every patch has been applied
+ */
+struct PatchSet<'a> {
+    patches: Vec<Patch>,
+    allocator: Option<&'a dyn EngineAllocator>,
+    allocated: Vec<usize>,
+    replaced: Vec<usize>
+}
+
+impl<'a> PatchSet<'a> {
+    fn push(&mut self, patch: Patch) {
+        self.patches.push(patch);
+    }
+
+    /**
+     * Where count elements of element_size bytes can be written for the TArray header at address
+     * The existing buffer when they fit, otherwise a zeroed engine allocation that the header is repointed to
+     */
+    fn reserve(&mut self, mem: &dyn MemoryReader, name: &str, address: usize, count: usize, element_size: usize) -> ReflectionResult<usize> {
+        let array: TArray<()> = TArray::new(address);
+        let data: usize = array.data(mem)?;
+        let capacity: usize = if data == 0 { 0 } else { array.capacity(mem)? };
+
+        if count <= capacity {
+            return Ok(data);
+        }
+
+        let allocator: &dyn EngineAllocator = match self.allocator {
+            Some(allocator) => allocator,
+            None => return Err(ReflectionError::CapacityExceeded { name: name.to_string(), needed: count, capacity: capacity })
+        };
+
+        let new_data: usize = allocator.malloc(count * element_size, DEFAULT_ALIGNMENT)?;
+        self.allocated.push(new_data);
+
+        if data != 0 {
+            self.replaced.push(data);
+        }
+
+        self.push(Patch::Bytes { address: new_data, bytes: vec![0u8; count * element_size] });
+        self.push(Patch::Bytes { address: address + TARRAY_DATA_OFFSET, bytes: (new_data as u64).to_le_bytes().to_vec() });
+        self.push(Patch::Bytes { address: address + TARRAY_MAX_OFFSET, bytes: (count as i32).to_le_bytes().to_vec() });
+
+        return Ok(new_data);
+    }
+}
+
+/**
+ * Writes a property of a live object by name, e.g. write_property(mem, gnames, None, player_controller, "FOVAngle", &PropertyValue::Float(110.0))
+ */
+pub fn write_property(mem: &mut dyn MemoryWriter, gnames: TArray, allocator: Option<&dyn EngineAllocator>, object: UObjectRef, name: &str, value: &PropertyValue) -> ReflectionResult<()> {
+    let property: PropertyInfo = find_property(&*mem, gnames, object, name)?;
+
+    return write_value(mem, gnames, allocator, &property, object.address(), value);
+}
+
+/**
+ * Writes a property into a container (object, struct or params buffer) starting at base
+ * The value is checked against the property's class and ElementSize first, nothing is written unless all of it checks out
+ * The write is all or nothing, if any of it fails the container is left as it was and buffers allocated for it are freed
+ * Structs may list only the fields that should change, static arrays may give fewer values than ArrayDim
+ * Strings and dynamic arrays are written into the buffer the game already allocated
+ * When they do not fit they are moved into a new buffer from allocator (the game frees these, so it has to be the engine's), without one they fail instead
+ * Arrays of strings, arrays or structs are never moved, their elements own memory of their own
+ */
+pub fn write_value(mem: &mut dyn MemoryWriter, gnames: TArray, allocator: Option<&dyn EngineAllocator>, property: &PropertyInfo, base: usize, value: &PropertyValue) -> ReflectionResult<()> {
+    let mut patches: PatchSet = PatchSet { patches: Vec::new(), allocator: allocator, allocated: Vec::new(), replaced: Vec::new() };
+
+    let result: ReflectionResult<()> = encode_value(&*mem, gnames, property, base, value, 0, &mut patches).and_then(|_| apply_patches(mem, &patches.patches));
+
+    if let Err(error) = result {
+        if let Some(allocator) = allocator {
+            for address in patches.allocated.iter() {
+                allocator.free(*address);
+            }
+        }
+        return Err(error);
+    }
+
+    if let Some(allocator) = allocator {
+        for address in patches.replaced.iter() {
+            allocator.free(*address);
+        }
+    }
+
+    return Ok(());
+}
+
+fn encode_value(mem: &dyn MemoryReader, gnames: TArray, property: &PropertyInfo, base: usize, value: &PropertyValue, depth: usize, patches: &mut PatchSet) -> ReflectionResult<()> {
+    let address: usize = base + property.offset;
+
+    if property.array_dim <= 1 {
+        return encode_element(mem, gnames, property, address, value, depth, patches);
+    }
+
+    let values: &Vec<PropertyValue> = match value {
+        PropertyValue::Array(values) => values,
+        _ => return Err(ReflectionError::TypeMismatch { name: property.name.clone(), expected: format!("an array of {} {}", property.array_dim, property.class_name) })
+    };
+
+    if values.len() > property.array_dim {
+        return Err(ReflectionError::CapacityExceeded { name: property.name.clone(), needed: values.len(), capacity: property.array_dim });
+    }
+
+    for (idx, element) in values.iter().enumerate() {
+        encode_element(mem, gnames, property, address + (idx * property.element_size), element, depth, patches)?;
+    }
+
+    return Ok(());
+}
+
+/**
+ * Checks a single element against its property and queues the bytes that represent it
+ */
+fn encode_element(mem: &dyn MemoryReader, gnames: TArray, info: &PropertyInfo, address: usize, value: &PropertyValue, depth: usize, patches: &mut PatchSet) -> ReflectionResult<()> {
+    let name: &str = &info.name;
+    let property: UPropertyRef = info.property;
+    let class_name: &str = &info.class_name;
+    let element_size: usize = info.element_size;
+
+    if depth > MAX_STRUCT_DEPTH {
+        return Err(ReflectionError::ChainTooLong { address: property.0.address() });
+    }
+
+    if let Some(expected) = expected_element_size(class_name) {
+        if element_size != expected {
+            return Err(ReflectionError::SizeMismatch { name: name.to_string(), expected: expected, actual: element_size });
+        }
+    }
+
+    match (class_name, value) {
+        ("IntProperty", PropertyValue::Int(value)) => patches.push(Patch::Bytes { address: address, bytes: value.to_le_bytes().to_vec() }),
+        ("FloatProperty", PropertyValue::Float(value)) => patches.push(Patch::Bytes { address: address, bytes: value.to_le_bytes().to_vec() }),
+        ("BoolProperty", PropertyValue::Bool(value)) => {
+            let mask: u32 = bool_property_mask(mem, property)?;
+            patches.push(Patch::Bits { address: address, mask: mask, set: *value });
+        }
+        ("ByteProperty", PropertyValue::Byte(value)) => patches.push(Patch::Bytes { address: address, bytes: vec![*value] }),
+        ("ByteProperty", PropertyValue::Enum(value, value_name)) => {
+            let uenum: UEnumRef = match byte_property_enum(mem, property)? {
+                Some(uenum) => uenum,
+                None => return Err(ReflectionError::TypeMismatch { name: name.to_string(), expected: "a plain byte".to_string() })
+            };
+
+            let resolved: Option<u8> = if value_name.is_empty() {
+                uenum.name_of(mem, gnames, *value)?.map(|_| *value)
+            }
+            else {
+                uenum.value_of(mem, gnames, value_name)?
+            };
+
+            match resolved {
+                Some(resolved) => patches.push(Patch::Bytes { address: address, bytes: vec![resolved] }),
+                None => return Err(ReflectionError::TypeMismatch { name: name.to_string(), expected: format!("a value of {}", uenum.0.path_name(mem, gnames)?) })
+            }
+        }
+        ("NameProperty", PropertyValue::Name(fname, _)) => {
+            let mut bytes: Vec<u8> = fname.index.to_le_bytes().to_vec();
+            bytes.extend_from_slice(&fname.number.to_le_bytes());
+            patches.push(Patch::Bytes { address: address, bytes: bytes });
+        }
+        ("StrProperty", PropertyValue::Str(value)) => encode_fstring(mem, name, address, value, patches)?,
+        ("ObjectProperty" | "ComponentProperty", PropertyValue::Object(object)) => {
+            if let Some(object) = object {
+                let property_class: UObjectRef = object_property_class(mem, property)?;
+
+                if !is_a(mem, *object, property_class)? {
+                    return Err(ReflectionError::TypeMismatch { name: name.to_string(), expected: format!("an object of class {}", property_class.path_name(mem, gnames)?) });
+                }
+            }
+            patches.push(Patch::Bytes { address: address, bytes: (object.map_or(0, |object| object.address()) as u64).to_le_bytes().to_vec() });
+        }
+        ("ClassProperty", PropertyValue::Class(class)) => {
+            if let Some(class) = class {
+                let meta_class: UObjectRef = class_property_meta_class(mem, property)?;
+
+                if !UStructRef(*class).is_child_of(mem, UStructRef(meta_class))? {
+                    return Err(ReflectionError::TypeMismatch { name: name.to_string(), expected: format!("a subclass of {}", meta_class.path_name(mem, gnames)?) });
+                }
+            }
+            patches.push(Patch::Bytes { address: address, bytes: (class.map_or(0, |class| class.address()) as u64).to_le_bytes().to_vec() });
+        }
+        ("StructProperty", PropertyValue::Struct(fields)) => {
+            let ustruct: UStructRef = struct_property_struct(mem, property)?;
+
+            for (field_name, field_value) in fields.iter() {
+                let field: PropertyInfo = find_struct_property(mem, gnames, ustruct, field_name)?;
+                encode_value(mem, gnames, &field, address, field_value, depth + 1, patches)?;
+            }
+        }
+        ("ArrayProperty", PropertyValue::Array(values)) => {
+            let inner: PropertyInfo = PropertyInfo::read(mem, gnames, array_inner(mem, property)?)?;
+            let elements: TArray<()> = TArray::new(address);
+
+            let data: usize = if is_plain_data(&inner.class_name) {
+                patches.reserve(mem, name, address, values.len(), inner.element_size)?
+            }
+            else {
+                let data: usize = elements.data(mem)?;
+                let capacity: usize = if data == 0 { 0 } else { elements.capacity(mem)? };
+
+                if values.len() > capacity {
+                    return Err(ReflectionError::CapacityExceeded { name: name.to_string(), needed: values.len(), capacity: capacity });
+                }
+
+                data
+            };
+
+            for (idx, element) in values.iter().enumerate() {
+                encode_element(mem, gnames, &inner, data + (idx * inner.element_size), element, depth + 1, patches)?;
+            }
+            patches.push(Patch::Bytes { address: address + TARRAY_NUM_OFFSET, bytes: (values.len() as i32).to_le_bytes().to_vec() });
+        }
+        (_, PropertyValue::Unknown { class_name: value_class, bytes }) if value_class == class_name => {
+            if bytes.len() != element_size {
+                return Err(ReflectionError::SizeMismatch { name: name.to_string(), expected: element_size, actual: bytes.len() });
+            }
+            patches.push(Patch::Bytes { address: address, bytes: bytes.clone() });
+        }
+        _ => return Err(ReflectionError::TypeMismatch { name: name.to_string(), expected: class_name.to_string() })
+    }
+
+    return Ok(());
+}
+
+/**
+ * The ElementSize every property of a class has to have, None for classes whose size depends on the declaration (structs) or that are only written raw
+ */
+fn expected_element_size(class_name: &str) -> Option<usize> {
+    return match class_name {
+        "IntProperty" | "FloatProperty" | "BoolProperty" => Some(4),
+        "ByteProperty" => Some(1),
+        "NameProperty" | "ObjectProperty" | "ComponentProperty" | "ClassProperty" => Some(8),
+        "StrProperty" | "ArrayProperty" => Some(0x10),
+        _ => None
+    };
+}
+
+/**
+ * Whether an element is just its bytes, without any memory of its own that moving or dropping it would leak
+ */
+fn is_plain_data(class_name: &str) -> bool {
+    return expected_element_size(class_name).is_some() && class_name != "StrProperty" && class_name != "ArrayProperty";
+}
+
+/**
+ * Queues an FString overwrite, in place when it fits within Max (Num counts the terminator), otherwise in a new engine allocation
+ */
+fn encode_fstring(mem: &dyn MemoryReader, name: &str, address: usize, value: &str, patches: &mut PatchSet) -> ReflectionResult<()> {
+    let units: Vec<u16> = fstring_units(value);
+
+    if units.is_empty() {
+        patches.push(Patch::Bytes { address: address + TARRAY_NUM_OFFSET, bytes: 0i32.to_le_bytes().to_vec() });
+        return Ok(());
+    }
+    let data: usize = patches.reserve(mem, name, address, units.len(), 2)?;
+
+    patches.push(Patch::Bytes { address: data, bytes: units.iter().flat_map(|unit| unit.to_le_bytes()).collect() });
+    patches.push(Patch::Bytes { address: address + TARRAY_NUM_OFFSET, bytes: (units.len() as i32).to_le_bytes().to_vec() });
+
+    return Ok(());
+}
+
+/**
+ * The class an ObjectProperty is declared to hold
+ */
+pub fn object_property_class(mem: &dyn MemoryReader, property: UPropertyRef) -> ReflectionResult<UObjectRef> {
+    return mem.read_pointer(property.0.address() + field_layout().property_size + UOBJECTPROPERTY_PROPERTYCLASS_OFFSET).map(UObjectRef);
+}
+
+/**
+ * The class a ClassProperty's value has to be a subclass of
+ */
+pub fn class_property_meta_class(mem: &dyn MemoryReader, property: UPropertyRef) -> ReflectionResult<UObjectRef> {
+    return mem.read_pointer(property.0.address() + field_layout().property_size + UCLASSPROPERTY_METACLASS_OFFSET).map(UObjectRef);
+}
+
+/**
+ * The UScriptStruct a StructProperty holds
+ */
+pub fn struct_property_struct(mem: &dyn MemoryReader, property: UPropertyRef) -> ReflectionResult<UStructRef> {
+    return mem.read_pointer(property.0.address() + field_layout().property_size + USTRUCTPROPERTY_STRUCT_OFFSET).map(|address| UStructRef(UObjectRef(address)));
+}
+
+/**
+ * The property describing the elements of an ArrayProperty
+ */
+pub fn array_inner(mem: &dyn MemoryReader, property: UPropertyRef) -> ReflectionResult<UPropertyRef> {
+    return mem.read_pointer(property.0.address() + field_layout().property_size + UARRAYPROPERTY_INNER_OFFSET).map(|address| UPropertyRef(UObjectRef(address)));
+}
+
+/**
+ * The UEnum a ByteProperty holds values of, None for plain bytes
+ */
+pub fn byte_property_enum(mem: &dyn MemoryReader, property: UPropertyRef) -> ReflectionResult<Option<UEnumRef>> {
+    return read_optional_object(mem, property.0.address() + field_layout().property_size + UBYTEPROPERTY_ENUM_OFFSET).map(|object| object.map(UEnumRef));
+}
+
+/**
+ * Mask selecting a BoolProperty's bit inside its 32 bit word
+ */
+pub fn bool_property_mask(mem: &dyn MemoryReader, property: UPropertyRef) -> ReflectionResult<u32> {
+    return mem.read_u32(property.0.address() + field_layout().property_size + UBOOLPROPERTY_BITMASK_OFFSET);
+}
+
+fn read_optional_object(mem: &dyn MemoryReader, address: usize) -> ReflectionResult<Option<UObjectRef>> {
+    let object: usize = mem.read_usize(address)?;
+
+    if object == 0 {
+        return Ok(None);
+    }
+
+    return Ok(Some(UObjectRef(object)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{TestAllocator, TestImage};
+
+    /**
+     * Memory that refuses writes touching [start, end), like a read only page in the middle of a container
+     */
+    struct GuardedMemory<'a> {
+        mem: &'a mut dyn MemoryWriter,
+        start: usize,
+        end: usize
+    }
+
+    impl MemoryReader for GuardedMemory<'_> {
+        fn read_bytes(&self, address: usize, buf: &mut [u8]) -> bool {
+            return self.mem.read_bytes(address, buf);
+        }
+    }
+
+    impl MemoryWriter for GuardedMemory<'_> {
+        fn write_bytes(&mut self, address: usize, data: &[u8]) -> bool {
+            if address < self.end && address + data.len() > self.start {
+                return false;
+            }
+
+            return self.mem.write_bytes(address, data);
+        }
+    }
+
+    /**
+     * A Stats class holding Health (int), Speed (float), bDead (bool bit 2), Title (string), Scores (int array) and Pair (struct of two ints)
+     */
+    struct Fixture {
+        image: TestImage,
+        object: UObjectRef
+    }
+
+    fn fixture() -> Fixture {
+        let mut image: TestImage = TestImage::new();
+        let game: usize = image.add_package("Game");
+        let class: usize = image.add_class("Stats", game, 0, 0x150);
+        let script_struct: usize = image.field_class("ScriptStruct");
+        let pair: usize = image.add_struct("Pair", class, script_struct, 0, 0x8);
+        image.add_property(pair, "IntProperty", "A", 0x0, 4, &[]);
+        image.add_property(pair, "IntProperty", "B", 0x4, 4, &[]);
+        let inner: usize = image.add_inner_property(class, "IntProperty", 4, &[]);
+
+        image.add_property(class, "IntProperty", "Health", 0x100, 4, &[]);
+        image.add_property(class, "FloatProperty", "Speed", 0x104, 4, &[]);
+        image.add_property(class, "BoolProperty", "bDead", 0x108, 4, &[0x2]);
+        image.add_property(class, "StrProperty", "Title", 0x110, 0x10, &[]);
+        image.add_property(class, "ArrayProperty", "Scores", 0x120, 0x10, &[inner]);
+        image.add_property(class, "StructProperty", "Pair", 0x130, 0x8, &[pair]);
+
+        let object: usize = image.add_object_sized("Stats_0", game, class, 0x150);
+        image.mem.write_i32(object + 0x100, 100).unwrap();
+        image.mem.write_u32(object + 0x108, 0x1).unwrap();
+        image.write_fstring(object + 0x110, "Hi", 4);
+
+        return Fixture { image: image, object: UObjectRef(object) };
+    }
+
+    fn read(fixture: &Fixture, name: &str) -> PropertyValue {
+        return read_property(&fixture.image.mem, fixture.image.gnames, fixture.object, name).unwrap();
+    }
+
+    #[test]
+    fn reads_properties_by_class() {
+        let fixture: Fixture = fixture();
+
+        assert_eq!(read(&fixture, "Health"), PropertyValue::Int(100));
+        assert_eq!(read(&fixture, "health"), PropertyValue::Int(100));
+        assert_eq!(read(&fixture, "bDead"), PropertyValue::Bool(false));
+        assert_eq!(read(&fixture, "Title"), PropertyValue::Str("Hi".to_string()));
+        assert_eq!(read(&fixture, "Scores"), PropertyValue::Array(Vec::new()));
+        assert_eq!(read(&fixture, "Pair"), PropertyValue::Struct(vec![("A".to_string(), PropertyValue::Int(0)), ("B".to_string(), PropertyValue::Int(0))]));
+        assert!(matches!(read_property(&fixture.image.mem, fixture.image.gnames, fixture.object, "Missing"), Err(ReflectionError::PropertyNotFound { .. })));
+    }
+
+    #[test]
+    fn writes_round_trip() {
+        let mut fixture: Fixture = fixture();
+        let gnames: TArray = fixture.image.gnames;
+        let object: UObjectRef = fixture.object;
+
+        write_property(&mut fixture.image.mem, gnames, None, object, "Health", &PropertyValue::Int(-5)).unwrap();
+        write_property(&mut fixture.image.mem, gnames, None, object, "Speed", &PropertyValue::Float(1.5)).unwrap();
+        write_property(&mut fixture.image.mem, gnames, None, object, "bDead", &PropertyValue::Bool(true)).unwrap();
+        write_property(&mut fixture.image.mem, gnames, None, object, "Title", &PropertyValue::Str("Bye".to_string())).unwrap();
+        write_property(&mut fixture.image.mem, gnames, None, object, "Pair", &PropertyValue::Struct(vec![("B".to_string(), PropertyValue::Int(7))])).unwrap();
+
+        assert_eq!(read(&fixture, "Health"), PropertyValue::Int(-5));
+        assert_eq!(read(&fixture, "Speed"), PropertyValue::Float(1.5));
+        assert_eq!(read(&fixture, "bDead"), PropertyValue::Bool(true));
+        // The bool only owns its bit, the one next to it is left alone
+        assert_eq!(fixture.image.mem.read_u32(object.address() + 0x108).unwrap(), 0x3);
+        assert_eq!(read(&fixture, "Title"), PropertyValue::Str("Bye".to_string()));
+        assert_eq!(read(&fixture, "Pair"), PropertyValue::Struct(vec![("A".to_string(), PropertyValue::Int(0)), ("B".to_string(), PropertyValue::Int(7))]));
+    }
+
+    #[test]
+    fn mismatched_values_write_nothing() {
+        let mut fixture: Fixture = fixture();
+        let gnames: TArray = fixture.image.gnames;
+        let object: UObjectRef = fixture.object;
+        let value: PropertyValue = PropertyValue::Struct(vec![("A".to_string(), PropertyValue::Int(1)), ("B".to_string(), PropertyValue::Float(2.0))]);
+
+        assert!(matches!(write_property(&mut fixture.image.mem, gnames, None, object, "Pair", &value), Err(ReflectionError::TypeMismatch { .. })));
+        assert!(matches!(write_property(&mut fixture.image.mem, gnames, None, object, "Title", &PropertyValue::Str("Too long".to_string())), Err(ReflectionError::CapacityExceeded { .. })));
+        assert_eq!(read(&fixture, "Pair"), PropertyValue::Struct(vec![("A".to_string(), PropertyValue::Int(0)), ("B".to_string(), PropertyValue::Int(0))]));
+        assert_eq!(read(&fixture, "Title"), PropertyValue::Str("Hi".to_string()));
+    }
+
+    #[test]
+    fn failed_writes_are_rolled_back() {
+        let mut fixture: Fixture = fixture();
+        let gnames: TArray = fixture.image.gnames;
+        let object: UObjectRef = fixture.object;
+        let value: PropertyValue = PropertyValue::Struct(vec![("A".to_string(), PropertyValue::Int(1)), ("B".to_string(), PropertyValue::Int(2))]);
+
+        {
+            let mut guarded: GuardedMemory = GuardedMemory { mem: &mut fixture.image.mem, start: object.address() + 0x134, end: object.address() + 0x138 };
+            assert!(matches!(write_property(&mut guarded, gnames, None, object, "Pair", &value), Err(ReflectionError::Unwritable { .. })));
+        }
+
+        assert_eq!(read(&fixture, "Pair"), PropertyValue::Struct(vec![("A".to_string(), PropertyValue::Int(0)), ("B".to_string(), PropertyValue::Int(0))]));
+    }
+
+    #[test]
+    fn grown_buffers_come_from_the_allocator() {
+        let mut fixture: Fixture = fixture();
+        let gnames: TArray = fixture.image.gnames;
+        let object: UObjectRef = fixture.object;
+        let allocator: TestAllocator = fixture.image.allocator(0x1000);
+        let old_data: usize = fixture.image.mem.read_usize(object.address() + 0x110).unwrap();
+
+        write_property(&mut fixture.image.mem, gnames, Some(&allocator), object, "Title", &PropertyValue::Str("A longer title".to_string())).unwrap();
+        write_property(&mut fixture.image.mem, gnames, Some(&allocator), object, "Scores", &PropertyValue::Array(vec![PropertyValue::Int(3), PropertyValue::Int(4)])).unwrap();
+
+        assert_eq!(read(&fixture, "Title"), PropertyValue::Str("A longer title".to_string()));
+        assert_eq!(read(&fixture, "Scores"), PropertyValue::Array(vec![PropertyValue::Int(3), PropertyValue::Int(4)]));
+        assert_eq!(allocator.allocated.borrow().len(), 2);
+        assert_eq!(*allocator.freed.borrow(), vec![old_data]);
+    }
+
+    #[test]
+    fn failed_writes_free_their_allocations() {
+        let mut fixture: Fixture = fixture();
+        let gnames: TArray = fixture.image.gnames;
+        let object: UObjectRef = fixture.object;
+        let allocator: TestAllocator = fixture.image.allocator(0x1000);
+
+        {
+            // Growing Title succeeds, but the Num of Scores sits on a page that can not be written
+            let mut guarded: GuardedMemory = GuardedMemory { mem: &mut fixture.image.mem, start: object.address() + 0x128, end: object.address() + 0x12C };
+            let title: PropertyInfo = find_property(&guarded, gnames, object, "Title").unwrap();
+            let scores: PropertyInfo = find_property(&guarded, gnames, object, "Scores").unwrap();
+
+            write_value(&mut guarded, gnames, Some(&allocator), &title, object.address(), &PropertyValue::Str("A longer title".to_string())).unwrap();
+            assert!(write_value(&mut guarded, gnames, Some(&allocator), &scores, object.address(), &PropertyValue::Array(vec![PropertyValue::Int(3)])).is_err());
+        }
+
+        assert_eq!(allocator.live().len(), 1);
+        assert_eq!(read(&fixture, "Title"), PropertyValue::Str("A longer title".to_string()));
+        assert_eq!(read(&fixture, "Scores"), PropertyValue::Array(Vec::new()));
+        assert_eq!(fixture.image.mem.read_usize(object.address() + 0x120).unwrap(), 0);
+    }
+}
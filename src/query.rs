@@ -0,0 +1,503 @@
+use std::fmt;
+
+use crate::flags::ObjectFlags;
+use crate::path::ObjectPath;
+use crate::registry::{ObjectRegistry, RegistryEntry};
+
+/**
+ * Small search language over the object registry, terms are separated by whitespace and every term has to match
+ *
+ *   SetFOV                          glob on the object's short name
+ *   Engine.PlayerController.*       glob on the path name (any pattern containing a .)
+ *   name:PoplarPlayerController_*   glob on the short name
+ *   path:*.PersistentLevel.*        glob on the path name
+ *   class:PoplarGame.PoplarCamera   glob on the class, path name if the pattern contains a ., short name otherwise
+ *   isa:Engine.PlayerController     glob on the class or any of its super classes, same path/short name rule as class
+ *   outer:PersistentLevel           glob on any outer in the chain, same path/short name rule as class
+ *   flag:Transient                  only objects with the ObjectFlag set, with or without the RF_ prefix
+ *   -flag:PendingKill               skip objects with the ObjectFlag set
+ *   -default                        skip class default objects, archetypes and anything inside them
+ *
+ * Globs use * and ?, and are case insensitive like engine names
+ * A term is only split into key:value when the part before the first : is a plain word, so paths using the subobject delimiter
+ * (Caverns_P.TheWorld:PersistentLevel, Default__Foo:Sub*) are bare patterns, a plain word that is not a key is still an error
+ */
+#[derive(Clone, Debug, Default)]
+pub struct ObjectQuery {
+    pub names: Vec<Glob>,
+    pub paths: Vec<Glob>,
+    pub classes: Vec<Glob>,
+    pub ancestors: Vec<Glob>,
+    pub outers: Vec<Glob>,
+    pub required_flags: u64,
+    pub excluded_flags: u64,
+    pub exclude_defaults: bool
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum QueryError {
+    UnknownKey(String),
+    EmptyValue(String),
+    UnknownFlag(String)
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            QueryError::UnknownKey(key) => write!(f, "unknown query key {}", key),
+            QueryError::EmptyValue(key) => write!(f, "query key {} needs a value", key),
+            QueryError::UnknownFlag(flag) => write!(f, "unknown object flag {}", flag)
+        };
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+impl ObjectQuery {
+    pub fn new() -> ObjectQuery {
+        return ObjectQuery::default();
+    }
+
+    pub fn parse(text: &str) -> Result<ObjectQuery, QueryError> {
+        let mut query: ObjectQuery = ObjectQuery::new();
+
+        for term in text.split_whitespace() {
+            if term.eq_ignore_ascii_case("-default") {
+                query.exclude_defaults = true;
+                continue;
+            }
+
+            let (key, value) = match term.split_once(':') {
+                Some((key, value)) if is_key(key) => (key, value),
+                _ => {
+                    query = query.with_pattern(term);
+                    continue;
+                }
+            };
+
+            if value.is_empty() {
+                return Err(QueryError::EmptyValue(key.to_string()));
+            }
+
+            match key.to_lowercase().as_str() {
+                "name" => query.names.push(Glob::new(value)),
+                "path" => query.paths.push(Glob::new(value)),
+                "class" => query.classes.push(Glob::new(value)),
+                "isa" => query.ancestors.push(Glob::new(value)),
+                "outer" => query.outers.push(Glob::new(value)),
+                "flag" => query.required_flags |= ObjectFlags::parse_flag(value).ok_or_else(|| QueryError::UnknownFlag(value.to_string()))?,
+                "-flag" => query.excluded_flags |= ObjectFlags::parse_flag(value).ok_or_else(|| QueryError::UnknownFlag(value.to_string()))?,
+                _ => return Err(QueryError::UnknownKey(key.to_string()))
+            }
+        }
+
+        return Ok(query);
+    }
+
+    /**
+     * Adds a bare pattern, matched against the path name if it contains a . and the short name otherwise
+     */
+    pub fn with_pattern(mut self, pattern: &str) -> ObjectQuery {
+        if pattern.contains(['.', ':']) {
+            self.paths.push(Glob::new(pattern));
+        }
+        else {
+            self.names.push(Glob::new(pattern));
+        }
+        return self;
+    }
+
+    pub fn with_class(mut self, pattern: &str) -> ObjectQuery {
+        self.classes.push(Glob::new(pattern));
+        return self;
+    }
+
+    /**
+     * Matches instances of the class and of every subclass
+     */
+    pub fn with_ancestor(mut self, pattern: &str) -> ObjectQuery {
+        self.ancestors.push(Glob::new(pattern));
+        return self;
+    }
+
+    pub fn with_outer(mut self, pattern: &str) -> ObjectQuery {
+        self.outers.push(Glob::new(pattern));
+        return self;
+    }
+
+    /**
+     * Only objects with every one of flags set
+     */
+    pub fn with_flags(mut self, flags: u64) -> ObjectQuery {
+        self.required_flags |= flags;
+        return self;
+    }
+
+    /**
+     * Skips objects with any of flags set
+     */
+    pub fn without_flags(mut self, flags: u64) -> ObjectQuery {
+        self.excluded_flags |= flags;
+        return self;
+    }
+
+    pub fn exclude_defaults(mut self) -> ObjectQuery {
+        self.exclude_defaults = true;
+        return self;
+    }
+
+    pub fn matches(&self, registry: &ObjectRegistry, entry: &RegistryEntry) -> bool {
+        let path: ObjectPath = match ObjectPath::parse(&entry.path) {
+            Some(path) => path,
+            None => return false
+        };
+
+        if entry.flags.0 & self.required_flags != self.required_flags || entry.flags.contains(self.excluded_flags) {
+            return false;
+        }
+
+        if self.exclude_defaults && registry.is_template(entry) {
+            return false;
+        }
+
+        if !self.names.iter().all(|glob| glob.matches(&path.name)) {
+            return false;
+        }
+
+        if !self.paths.iter().all(|glob| glob.matches(&path.path_name())) {
+            return false;
+        }
+
+        if !self.classes.is_empty() {
+            let class_path: &str = match registry.class_path(entry) {
+                Some(class_path) => class_path,
+                None => return false
+            };
+
+            if !self.classes.iter().all(|glob| glob.matches_path(class_path)) {
+                return false;
+            }
+        }
+
+        if !self.ancestors.is_empty() {
+            let chain: Vec<&str> = registry.class_chain(entry.class).iter().filter_map(|class| registry.get_by_address(class.address())).map(|class| class.path.as_str()).collect();
+
+            if !self.ancestors.iter().all(|glob| chain.iter().any(|class_path| glob.matches_path(class_path))) {
+                return false;
+            }
+        }
+
+        for glob in self.outers.iter() {
+            let mut outer_path: String = String::new();
+            let mut found: bool = false;
+
+            for outer in path.outers.iter() {
+                if !outer_path.is_empty() {
+                    outer_path.push('.');
+                }
+                outer_path.push_str(outer);
+
+                if glob.matches_path(&outer_path) {
+                    found = true;
+                    break;
+                }
+            }
+
+            if !found {
+                return false;
+            }
+        }
+
+        return true;
+    }
+
+    /**
+     * Every matching object in GObjects order
+     */
+    pub fn run<'a>(&self, registry: &'a ObjectRegistry) -> Vec<&'a RegistryEntry> {
+        return self.candidates(registry).into_iter().filter(|entry| self.matches(registry, entry)).collect();
+    }
+
+    pub fn first<'a>(&self, registry: &'a ObjectRegistry) -> Option<&'a RegistryEntry> {
+        return self.candidates(registry).into_iter().find(|entry| self.matches(registry, entry));
+    }
+
+    /**
+     * Uses the registry's class index when a class is given as an exact path name, otherwise every object is a candidate
+     */
+    fn candidates<'a>(&self, registry: &'a ObjectRegistry) -> Vec<&'a RegistryEntry> {
+        for glob in self.classes.iter() {
+            if let Some(class_path) = glob.literal_path() {
+                if registry.find_by_path(class_path).is_some() {
+                    return registry.find_by_class(class_path);
+                }
+            }
+        }
+
+        for glob in self.ancestors.iter() {
+            if let Some(class) = glob.literal_path().and_then(|class_path| registry.find_by_path(class_path)) {
+                return registry.objects_of_subclasses(class);
+            }
+        }
+
+        return registry.iter().collect();
+    }
+}
+
+/**
+ * Whether the text before a : can be a query key, anything with a path delimiter or a wildcard in it is part of a pattern instead
+ */
+fn is_key(key: &str) -> bool {
+    let word: &str = key.strip_prefix('-').unwrap_or(key);
+    return !word.is_empty() && word.chars().all(|c| c.is_ascii_alphabetic());
+}
+
+/**
+ * Case insensitive glob supporting * (any run of characters) and ? (a single character)
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Glob {
+    source: String,
+    pattern: Vec<char>,
+    is_path: bool
+}
+
+impl Glob {
+    pub fn new(pattern: &str) -> Glob {
+        let pattern: String = pattern.replace(':', ".");
+
+        return Glob { is_path: pattern.contains('.'), pattern: pattern.to_lowercase().chars().collect(), source: pattern };
+    }
+
+    /**
+     * The pattern as written, if it is a full path without any wildcards
+     */
+    pub fn literal_path(&self) -> Option<&str> {
+        if !self.is_path || self.source.contains(['*', '?']) {
+            return None;
+        }
+        return Some(&self.source);
+    }
+
+    /**
+     * Matches a path name, only the last segment is compared when the pattern itself is not a path
+     */
+    pub fn matches_path(&self, path: &str) -> bool {
+        if self.is_path {
+            return self.matches(&path.replace(':', "."));
+        }
+
+        return self.matches(path.rsplit(['.', ':']).next().unwrap_or(path));
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        let text: Vec<char> = text.to_lowercase().chars().collect();
+
+        let mut p: usize = 0;
+        let mut t: usize = 0;
+        let mut star: Option<usize> = None;
+        let mut star_t: usize = 0;
+
+        while t < text.len() {
+            if p < self.pattern.len() && (self.pattern[p] == '?' || self.pattern[p] == text[t]) {
+                p += 1;
+                t += 1;
+            }
+            else if p < self.pattern.len() && self.pattern[p] == '*' {
+                star = Some(p);
+                star_t = t;
+                p += 1;
+            }
+            else if let Some(star_p) = star {
+                p = star_p + 1;
+                star_t += 1;
+                t = star_t;
+            }
+            else {
+                return false;
+            }
+        }
+
+        while p < self.pattern.len() && self.pattern[p] == '*' {
+            p += 1;
+        }
+
+        return p == self.pattern.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{RF_ARCHETYPE_OBJECT, RF_CLASS_DEFAULT_OBJECT, RF_PENDING_KILL, RF_PENDING_KILL_MASK, RF_TRANSIENT};
+    use crate::reflection::UObjectRef;
+    use crate::testing::TestImage;
+
+    /**
+     * Objects at the addresses the tests look for
+     */
+    struct Fixture {
+        registry: ObjectRegistry,
+        controller: usize,
+        default_controller: usize,
+        default_input: usize,
+        camera: usize,
+        dead_camera: usize
+    }
+
+    fn fixture() -> Fixture {
+        let mut image: TestImage = TestImage::new();
+        let engine: usize = image.add_package("Engine");
+        let poplar: usize = image.add_package("PoplarGame");
+        let map: usize = image.add_package("Slums_P");
+
+        let controller_class: usize = image.add_class("PlayerController", engine, 0, 0x100);
+        let poplar_controller_class: usize = image.add_class("PoplarPlayerController", poplar, controller_class, 0x100);
+        let input_class: usize = image.add_class("PlayerInput", engine, 0, 0x100);
+        let camera_class: usize = image.add_class("Camera", engine, 0, 0x100);
+        let world_class: usize = image.add_class("World", engine, 0, 0x100);
+        let level_class: usize = image.add_class("Level", engine, 0, 0x100);
+
+        let world: usize = image.add_object("TheWorld", map, world_class);
+        let level: usize = image.add_object("PersistentLevel", world, level_class);
+        let controller: usize = image.add_object("PoplarPlayerController_0", level, poplar_controller_class);
+        image.add_object("PlayerInput_0", controller, input_class);
+        let camera: usize = image.add_object("Camera_0", level, camera_class);
+        let dead_camera: usize = image.add_object("Camera_1", level, camera_class);
+        image.set_flags(dead_camera, RF_PENDING_KILL | RF_TRANSIENT);
+        image.set_flags(camera, RF_TRANSIENT);
+
+        let default_controller: usize = image.add_object("Default__PoplarPlayerController", poplar, poplar_controller_class);
+        image.set_flags(default_controller, RF_CLASS_DEFAULT_OBJECT);
+        let default_input: usize = image.add_object("PlayerInput_0", default_controller, input_class);
+        image.set_flags(default_input, RF_ARCHETYPE_OBJECT);
+
+        return Fixture {
+            registry: ObjectRegistry::build(&image.mem, image.gnames, image.gobjects).unwrap(),
+            controller: controller,
+            default_controller: default_controller,
+            default_input: default_input,
+            camera: camera,
+            dead_camera: dead_camera
+        };
+    }
+
+    fn addresses(query: &ObjectQuery, registry: &ObjectRegistry) -> Vec<usize> {
+        return query.run(registry).iter().map(|entry| entry.object.address()).collect();
+    }
+
+    #[test]
+    fn parses_every_key() {
+        let query: ObjectQuery = ObjectQuery::parse("name:Camera_* path:*.PersistentLevel.* CLASS:Engine.Camera isa:Camera outer:PersistentLevel flag:Transient -flag:RF_PendingKill -default Foo Engine.Bar").unwrap();
+
+        assert_eq!(query.names, vec![Glob::new("Camera_*"), Glob::new("Foo")]);
+        assert_eq!(query.paths, vec![Glob::new("*.PersistentLevel.*"), Glob::new("Engine.Bar")]);
+        assert_eq!(query.classes, vec![Glob::new("Engine.Camera")]);
+        assert_eq!(query.ancestors, vec![Glob::new("Camera")]);
+        assert_eq!(query.outers, vec![Glob::new("PersistentLevel")]);
+        assert_eq!(query.required_flags, RF_TRANSIENT);
+        assert_eq!(query.excluded_flags, RF_PENDING_KILL);
+        assert!(query.exclude_defaults);
+    }
+
+    #[test]
+    fn rejects_bad_terms() {
+        assert_eq!(ObjectQuery::parse("owner:Foo").unwrap_err(), QueryError::UnknownKey("owner".to_string()));
+        assert_eq!(ObjectQuery::parse("class:").unwrap_err(), QueryError::EmptyValue("class".to_string()));
+        assert_eq!(ObjectQuery::parse("flag:Shiny").unwrap_err(), QueryError::UnknownFlag("Shiny".to_string()));
+        assert_eq!(ObjectQuery::parse("-flag:").unwrap_err(), QueryError::EmptyValue("-flag".to_string()));
+    }
+
+    #[test]
+    fn bare_terms_may_use_the_subobject_delimiter() {
+        let query: ObjectQuery = ObjectQuery::parse("Slums_P.TheWorld:PersistentLevel.* Default__*:PlayerInput_?").unwrap();
+
+        assert_eq!(query.paths, vec![Glob::new("Slums_P.TheWorld:PersistentLevel.*"), Glob::new("Default__*:PlayerInput_?")]);
+        assert!(query.names.is_empty());
+
+        let f: Fixture = fixture();
+        assert_eq!(addresses(&ObjectQuery::parse("*.Default__PoplarPlayerController:PlayerInput_0").unwrap(), &f.registry), vec![f.default_input]);
+    }
+
+    #[test]
+    fn globs_are_case_insensitive() {
+        let glob: Glob = Glob::new("poplar*Controller_?");
+
+        assert!(glob.matches("PoplarPlayerController_0"));
+        assert!(glob.matches("POPLARController_9"));
+        assert!(!glob.matches("PoplarPlayerController_10"));
+        assert!(!glob.matches("PlayerController_0"));
+        assert!(Glob::new("*").matches(""));
+        assert!(Glob::new("a*b*c").matches("aXbYbZc"));
+        assert!(!Glob::new("a?c").matches("ac"));
+
+        assert!(Glob::new("PersistentLevel").matches_path("Slums_P.TheWorld:PersistentLevel"));
+        assert!(Glob::new("Slums_P.TheWorld.PersistentLevel").matches_path("Slums_P.TheWorld:PersistentLevel"));
+        assert_eq!(Glob::new("Engine.Camera").literal_path(), Some("Engine.Camera"));
+        assert_eq!(Glob::new("Engine.Cam*").literal_path(), None);
+        assert_eq!(Glob::new("Camera").literal_path(), None);
+    }
+
+    #[test]
+    fn candidates_come_from_the_class_index() {
+        let f: Fixture = fixture();
+
+        // An exact class path narrows the candidates to that class, isa to the class and its subclasses
+        assert_eq!(ObjectQuery::new().with_class("Engine.Camera").candidates(&f.registry).len(), 2);
+        assert_eq!(ObjectQuery::new().with_ancestor("Engine.PlayerController").candidates(&f.registry).len(), 2);
+        assert_eq!(ObjectQuery::new().with_class("Engine.Cam*").candidates(&f.registry).len(), f.registry.len());
+        assert_eq!(ObjectQuery::new().with_class("Engine.Missing").candidates(&f.registry).len(), f.registry.len());
+    }
+
+    #[test]
+    fn class_and_isa_filters() {
+        let f: Fixture = fixture();
+
+        assert_eq!(addresses(&ObjectQuery::new().with_class("PoplarGame.PoplarPlayerController"), &f.registry), vec![f.controller, f.default_controller]);
+        assert_eq!(addresses(&ObjectQuery::new().with_class("Engine.PlayerController"), &f.registry), Vec::<usize>::new());
+        assert_eq!(addresses(&ObjectQuery::new().with_ancestor("Engine.PlayerController"), &f.registry), vec![f.controller, f.default_controller]);
+        assert_eq!(addresses(&ObjectQuery::new().with_ancestor("PlayerController"), &f.registry), vec![f.controller, f.default_controller]);
+        assert_eq!(addresses(&ObjectQuery::new().with_class("camera"), &f.registry), vec![f.camera, f.dead_camera]);
+    }
+
+    #[test]
+    fn outer_constraints_match_any_outer() {
+        let f: Fixture = fixture();
+
+        let inside_level: ObjectQuery = ObjectQuery::new().with_class("Engine.Camera").with_outer("PersistentLevel");
+        assert_eq!(addresses(&inside_level, &f.registry), vec![f.camera, f.dead_camera]);
+
+        let inside_world: ObjectQuery = ObjectQuery::new().with_outer("Slums_P.TheWorld").with_outer("PoplarPlayerController_*");
+        assert_eq!(inside_world.first(&f.registry).unwrap().path, "Slums_P.TheWorld:PersistentLevel.PoplarPlayerController_0.PlayerInput_0");
+
+        assert!(ObjectQuery::new().with_class("Engine.Camera").with_outer("Engine").first(&f.registry).is_none());
+    }
+
+    #[test]
+    fn defaults_and_flags_are_filtered() {
+        let f: Fixture = fixture();
+
+        let live: ObjectQuery = ObjectQuery::new().with_ancestor("Engine.PlayerController").exclude_defaults();
+        assert_eq!(addresses(&live, &f.registry), vec![f.controller]);
+
+        let inputs: ObjectQuery = ObjectQuery::parse("name:PlayerInput_0 -default").unwrap();
+        assert_eq!(inputs.run(&f.registry).len(), 1);
+        assert!(inputs.run(&f.registry).iter().all(|entry| entry.object != UObjectRef(f.default_input)));
+
+        let cameras: ObjectQuery = ObjectQuery::new().with_class("Engine.Camera").without_flags(RF_PENDING_KILL_MASK);
+        assert_eq!(addresses(&cameras, &f.registry), vec![f.camera]);
+        assert_eq!(addresses(&ObjectQuery::parse("class:Engine.Camera flag:PendingKill").unwrap(), &f.registry), vec![f.dead_camera]);
+        assert_eq!(addresses(&ObjectQuery::parse("flag:Transient").unwrap(), &f.registry), vec![f.camera, f.dead_camera]);
+    }
+
+    #[test]
+    fn first_and_matches_agree_with_run() {
+        let f: Fixture = fixture();
+        let query: ObjectQuery = ObjectQuery::parse("Camera_*").unwrap();
+
+        assert_eq!(query.first(&f.registry).unwrap().object, UObjectRef(f.camera));
+        assert!(query.matches(&f.registry, f.registry.get_by_address(f.dead_camera).unwrap()));
+        assert!(!query.matches(&f.registry, f.registry.get_by_address(f.controller).unwrap()));
+        assert!(ObjectQuery::parse("name:Missing").unwrap().first(&f.registry).is_none());
+    }
+}
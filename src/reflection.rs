@@ -0,0 +1,603 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::OnceLock;
+
+use crate::error::{ReflectionError, ReflectionResult};
+use crate::flags::ObjectFlags;
+use crate::malloc::{DEFAULT_ALIGNMENT, EngineAllocator};
+use crate::memory::{MemoryReader, MemoryWriter};
+use crate::names::FName;
+
+/**
+ * UObject field offsets of the original build, used until a profile sets its own layout
+ */
+const UOBJECT_OBJECTFLAGS_OFFSET: usize = 0x10;
+const UOBJECT_INDEX_OFFSET: usize = 0x34;
+const UOBJECT_OUTER_OFFSET: usize = 0x38;
+const UOBJECT_NAME_OFFSET: usize = 0x40;
+const UOBJECT_CLASS_OFFSET: usize = 0x48;
+
+/**
+ * Where the UObject fields the reflection reads live, these move between builds
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UObjectLayout {
+    pub object_flags: usize,
+    pub index: usize,
+    pub outer: usize,
+    pub name: usize,
+    pub class: usize
+}
+
+impl Default for UObjectLayout {
+    fn default() -> UObjectLayout {
+        return DEFAULT_UOBJECT_LAYOUT;
+    }
+}
+
+const DEFAULT_UOBJECT_LAYOUT: UObjectLayout = UObjectLayout {
+    object_flags: UOBJECT_OBJECTFLAGS_OFFSET,
+    index: UOBJECT_INDEX_OFFSET,
+    outer: UOBJECT_OUTER_OFFSET,
+    name: UOBJECT_NAME_OFFSET,
+    class: UOBJECT_CLASS_OFFSET
+};
+
+static UOBJECT_LAYOUT: OnceLock<UObjectLayout> = OnceLock::new();
+
+/**
+ * Sets the layout every UObjectRef reads with, only the first call counts so it has to happen before anything is read
+ * Returns false if a layout was already set
+ */
+pub fn set_uobject_layout(layout: UObjectLayout) -> bool {
+    return UOBJECT_LAYOUT.set(layout).is_ok();
+}
+
+pub fn uobject_layout() -> &'static UObjectLayout {
+    return UOBJECT_LAYOUT.get().unwrap_or(&DEFAULT_UOBJECT_LAYOUT);
+}
+
+/**
+ * Separates an object from an outer that is not a package, but lives directly inside one (SUBOBJECT_DELIMITER)
+ */
+const SUBOBJECT_DELIMITER: char = ':';
+
+/**
+ * Real outer chains are a handful of objects deep, anything longer is a loop through garbage memory
+ */
+const MAX_OUTER_DEPTH: usize = 64;
+
+/**
+ * TArray header layout, shared by every TArray and FString
+ */
+pub const TARRAY_SIZE: usize = 0x10;
+pub const TARRAY_DATA_OFFSET: usize = 0x0;
+pub const TARRAY_NUM_OFFSET: usize = 0x8;
+pub const TARRAY_MAX_OFFSET: usize = 0xC;
+
+/**
+ * A value that can be stored inline in a TArray, SIZE is the stride between elements
+ */
+pub trait ArrayElement: Sized {
+    const SIZE: usize;
+
+    fn from_bytes(bytes: &[u8]) -> Self;
+
+    fn to_bytes(&self) -> Vec<u8>;
+}
+
+macro_rules! impl_array_element {
+    ($($ty:ty),*) => {
+        $(
+            impl ArrayElement for $ty {
+                const SIZE: usize = std::mem::size_of::<$ty>();
+
+                fn from_bytes(bytes: &[u8]) -> $ty {
+                    return <$ty>::from_le_bytes(bytes.try_into().unwrap());
+                }
+
+                fn to_bytes(&self) -> Vec<u8> {
+                    return self.to_le_bytes().to_vec();
+                }
+            }
+        )*
+    };
+}
+
+impl_array_element!(u8, u16, i32, u32, f32, u64);
+
+/**
+ * Pointers are 8 bytes in the game regardless of the host, same as read_usize
+ */
+impl ArrayElement for usize {
+    const SIZE: usize = 0x8;
+
+    fn from_bytes(bytes: &[u8]) -> usize {
+        return u64::from_bytes(bytes) as usize;
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        return (*self as u64).to_bytes();
+    }
+}
+
+impl ArrayElement for FName {
+    const SIZE: usize = 0x8;
+
+    fn from_bytes(bytes: &[u8]) -> FName {
+        return FName { index: u32::from_bytes(&bytes[0..4]), number: u32::from_bytes(&bytes[4..8]) };
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = self.index.to_bytes();
+        bytes.extend(self.number.to_bytes());
+        return bytes;
+    }
+}
+
+/**
+ * View of an engine TArray<T>, address points at the array header in game memory (Data pointer, then Num and Max)
+ * Nothing is cached, every call reads the header as it is right now
+ * T defaults to usize since most arrays the tool walks (GNames, GObjects) hold pointers
+ */
+pub struct TArray<T = usize> {
+    pub address: usize,
+    element: PhantomData<T>
+}
+
+// Not derived, that would require T: Copy even though only the address is copied
+impl<T> Clone for TArray<T> {
+    fn clone(&self) -> TArray<T> {
+        *self
+    }
+}
+
+impl<T> Copy for TArray<T> {}
+
+impl<T> fmt::Debug for TArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "TArray({:x})", self.address);
+    }
+}
+
+/**
+ * Header accessors, these do not depend on the element type so arrays whose element size is only known at runtime (ArrayProperty) can use them too
+ */
+impl<T> TArray<T> {
+    pub fn new(address: usize) -> TArray<T> {
+        return TArray { address: address, element: PhantomData };
+    }
+
+    /**
+     * Pointer to the first element, null for an array that never allocated
+     */
+    pub fn data(&self, mem: &dyn MemoryReader) -> ReflectionResult<usize> {
+        return mem.read_usize(self.address + TARRAY_DATA_OFFSET);
+    }
+
+    /**
+     * Num, checked against Max so a header read out of garbage memory is an error rather than a huge count
+     */
+    pub fn len(&self, mem: &dyn MemoryReader) -> ReflectionResult<usize> {
+        let num: i32 = mem.read_i32(self.address + TARRAY_NUM_OFFSET)?;
+        let max: i32 = mem.read_i32(self.address + TARRAY_MAX_OFFSET)?;
+
+        if num < 0 || num > max {
+            return Err(ReflectionError::InvalidArray { address: self.address, num: num, max: max });
+        }
+
+        return Ok(num as usize);
+    }
+
+    /**
+     * Max, how many elements fit before the engine has to reallocate
+     */
+    pub fn capacity(&self, mem: &dyn MemoryReader) -> ReflectionResult<usize> {
+        return mem.read_i32(self.address + TARRAY_MAX_OFFSET).map(|max| max.max(0) as usize);
+    }
+
+    pub fn is_empty(&self, mem: &dyn MemoryReader) -> ReflectionResult<bool> {
+        return Ok(self.len(mem)? == 0);
+    }
+
+    /**
+     * Address of element idx for elements of element_size bytes, idx has to be below Num
+     */
+    pub fn element_address(&self, mem: &dyn MemoryReader, idx: usize, element_size: usize) -> ReflectionResult<usize> {
+        let count: usize = self.len(mem)?;
+
+        if idx >= count {
+            return Err(ReflectionError::IndexOutOfRange { index: idx, count: count });
+        }
+
+        return Ok(mem.read_pointer(self.address + TARRAY_DATA_OFFSET)? + (element_size * idx));
+    }
+
+    /**
+     * Sets Num without touching the elements, only shrinking is allowed since growing would expose uninitialized memory
+     */
+    pub fn truncate(&self, mem: &mut dyn MemoryWriter, len: usize) -> ReflectionResult<()> {
+        if len >= self.len(&*mem)? {
+            return Ok(());
+        }
+
+        return mem.write_i32(self.address + TARRAY_NUM_OFFSET, len as i32);
+    }
+}
+
+impl<T: ArrayElement> TArray<T> {
+    pub fn get(&self, mem: &dyn MemoryReader, idx: usize) -> ReflectionResult<T> {
+        let mut bytes: Vec<u8> = vec![0u8; T::SIZE];
+        mem.read_exact(self.element_address(mem, idx, T::SIZE)?, &mut bytes)?;
+        return Ok(T::from_bytes(&bytes));
+    }
+
+    /**
+     * Every element in one read
+     */
+    pub fn to_vec(&self, mem: &dyn MemoryReader) -> ReflectionResult<Vec<T>> {
+        let count: usize = self.len(mem)?;
+
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut bytes: Vec<u8> = vec![0u8; count * T::SIZE];
+        mem.read_exact(self.data(mem)?, &mut bytes)?;
+
+        return Ok(bytes.chunks_exact(T::SIZE).map(T::from_bytes).collect());
+    }
+
+    /**
+     * Iterates the elements one read at a time, Num is read once up front so elements added while iterating are not visited
+     * An element that fails to read is yielded as the error, the rest of the array is still visited
+     */
+    pub fn iter<'a>(&self, mem: &'a dyn MemoryReader) -> ReflectionResult<TArrayIter<'a, T>> {
+        return Ok(TArrayIter { mem: mem, array: *self, idx: 0, count: self.len(mem)? });
+    }
+
+    /**
+     * Overwrites an existing element, idx has to be below Num
+     */
+    pub fn set(&self, mem: &mut dyn MemoryWriter, idx: usize, value: &T) -> ReflectionResult<()> {
+        let address: usize = self.element_address(&*mem, idx, T::SIZE)?;
+        return mem.write_exact(address, &value.to_bytes());
+    }
+
+    /**
+     * Makes room for at least capacity elements, reallocating Data through the engine's allocator when Max is too small like FArray::Realloc does
+     * The old allocation is handed to the same allocator, so this is only safe on arrays the engine owns
+     */
+    pub fn reserve(&self, mem: &mut dyn MemoryWriter, allocator: &dyn EngineAllocator, capacity: usize) -> ReflectionResult<()> {
+        if capacity <= self.capacity(&*mem)? {
+            return Ok(());
+        }
+
+        let old_data: usize = self.data(&*mem)?;
+        let new_data: usize = allocator.realloc(old_data, capacity * T::SIZE, DEFAULT_ALIGNMENT)?;
+
+        mem.write_usize(self.address + TARRAY_DATA_OFFSET, new_data)?;
+
+        return mem.write_i32(self.address + TARRAY_MAX_OFFSET, capacity as i32);
+    }
+
+    /**
+     * Appends an element, growing the same way FArray::AddZeroed does (Num + 3/8 Num + 16) when it does not fit
+     */
+    pub fn push(&self, mem: &mut dyn MemoryWriter, allocator: &dyn EngineAllocator, value: &T) -> ReflectionResult<()> {
+        let count: usize = self.len(&*mem)?;
+
+        if count >= self.capacity(&*mem)? {
+            self.reserve(mem, allocator, grown_capacity(count + 1))?;
+        }
+
+        mem.write_exact(self.data(&*mem)? + (count * T::SIZE), &value.to_bytes())?;
+
+        return mem.write_i32(self.address + TARRAY_NUM_OFFSET, (count + 1) as i32);
+    }
+
+    /**
+     * Replaces the whole contents with values, reallocating only when they do not fit
+     */
+    pub fn assign(&self, mem: &mut dyn MemoryWriter, allocator: &dyn EngineAllocator, values: &[T]) -> ReflectionResult<()> {
+        self.reserve(mem, allocator, values.len())?;
+
+        if !values.is_empty() {
+            let bytes: Vec<u8> = values.iter().flat_map(|value| value.to_bytes()).collect();
+            mem.write_exact(self.data(&*mem)?, &bytes)?;
+        }
+
+        return mem.write_i32(self.address + TARRAY_NUM_OFFSET, values.len() as i32);
+    }
+}
+
+fn grown_capacity(needed: usize) -> usize {
+    return needed + (3 * needed / 8) + 16;
+}
+
+pub struct TArrayIter<'a, T> {
+    mem: &'a dyn MemoryReader,
+    array: TArray<T>,
+    idx: usize,
+    count: usize
+}
+
+impl<'a, T: ArrayElement> Iterator for TArrayIter<'a, T> {
+    type Item = ReflectionResult<T>;
+
+    fn next(&mut self) -> Option<ReflectionResult<T>> {
+        if self.idx >= self.count {
+            return None;
+        }
+
+        let element: ReflectionResult<T> = self.array.get(self.mem, self.idx);
+        self.idx += 1;
+
+        return Some(element);
+    }
+}
+
+/**
+ * Handle to a UObject living in game memory
+ * Nothing is cached, every accessor reads the object as it is right now, so a handle never goes stale the way a copied out name would
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct UObjectRef(pub usize);
+
+impl UObjectRef {
+    pub fn address(&self) -> usize {
+        return self.0;
+    }
+
+    /**
+     * Index of the object inside GObjects
+     */
+    pub fn index(&self, mem: &dyn MemoryReader) -> ReflectionResult<u32> {
+        return mem.read_u32(self.0 + uobject_layout().index);
+    }
+
+    /**
+     * Flags change over the object's lifetime (pending kill, transient), so they are always read fresh
+     */
+    pub fn flags(&self, mem: &dyn MemoryReader) -> ReflectionResult<ObjectFlags> {
+        return mem.read_u64(self.0 + uobject_layout().object_flags).map(ObjectFlags);
+    }
+
+    pub fn fname(&self, mem: &dyn MemoryReader) -> ReflectionResult<FName> {
+        return FName::read(mem, self.0 + uobject_layout().name);
+    }
+
+    /**
+     * The object's own name with its instance suffix, without any outers
+     */
+    pub fn name(&self, mem: &dyn MemoryReader, gnames: TArray) -> ReflectionResult<String> {
+        return self.fname(mem)?.resolve(mem, gnames);
+    }
+
+    pub fn outer(&self, mem: &dyn MemoryReader) -> ReflectionResult<Option<UObjectRef>> {
+        let outer_address: usize = mem.read_usize(self.0 + uobject_layout().outer)?;
+
+        if outer_address == 0 {
+            return Ok(None);
+        }
+
+        return Ok(Some(UObjectRef(outer_address)));
+    }
+
+    /**
+     * Every object has a class, a null class means the object is half constructed or already destroyed
+     */
+    pub fn class(&self, mem: &dyn MemoryReader) -> ReflectionResult<UObjectRef> {
+        return mem.read_pointer(self.0 + uobject_layout().class).map(UObjectRef);
+    }
+
+    /**
+     * Every outer from the outermost package inwards, then the object itself, e.g. Engine.PlayerController.SetFOV (UObject::GetPathName)
+     * Like the engine, an outer that is not a package but sits directly in one is followed by the : subobject delimiter, e.g. TheWorld:PersistentLevel
+     */
+    pub fn path_name(&self, mem: &dyn MemoryReader, gnames: TArray) -> ReflectionResult<String> {
+        let mut chain: Vec<UObjectRef> = vec![*self];
+
+        let mut outer: Option<UObjectRef> = self.outer(mem)?;
+
+        while let Some(current) = outer {
+            if chain.len() > MAX_OUTER_DEPTH {
+                return Err(ReflectionError::ChainTooLong { address: self.0 });
+            }
+
+            chain.push(current);
+            outer = current.outer(mem)?;
+        }
+
+        let mut path: String = String::new();
+
+        for idx in (0..chain.len()).rev() {
+            if let Some(outer) = chain.get(idx + 1) {
+                let subobject: bool = match chain.get(idx + 2) {
+                    Some(outer_outer) => !outer.is_package(mem, gnames)? && outer_outer.is_package(mem, gnames)?,
+                    None => false
+                };
+
+                path.push(if subobject { SUBOBJECT_DELIMITER } else { '.' });
+            }
+
+            path.push_str(&chain[idx].name(mem, gnames)?);
+        }
+
+        return Ok(path);
+    }
+
+    fn is_package(&self, mem: &dyn MemoryReader, gnames: TArray) -> ReflectionResult<bool> {
+        return Ok(self.class_name(mem, gnames)? == "Package");
+    }
+
+    /**
+     * The short class name followed by the path name, e.g. Function Engine.PlayerController.SetFOV (UObject::GetFullName)
+     */
+    pub fn full_name(&self, mem: &dyn MemoryReader, gnames: TArray) -> ReflectionResult<String> {
+        return Ok(format!("{} {}", self.class_name(mem, gnames)?, self.path_name(mem, gnames)?));
+    }
+
+    /**
+     * Short name of the object's class, e.g. Function
+     */
+    pub fn class_name(&self, mem: &dyn MemoryReader, gnames: TArray) -> ReflectionResult<String> {
+        return self.class(mem)?.name(mem, gnames);
+    }
+
+    /**
+     * Path name of the object's class, e.g. Core.Function
+     */
+    pub fn class_path_name(&self, mem: &dyn MemoryReader, gnames: TArray) -> ReflectionResult<String> {
+        return self.class(mem)?.path_name(mem, gnames);
+    }
+}
+
+/**
+ * Walks GObjects as it currently is, skipping empty slots
+ * Num is read once when the iterator is created, objects created while iterating are not visited
+ */
+pub struct GObjectsIter<'a> {
+    slots: TArrayIter<'a, usize>
+}
+
+impl<'a> Iterator for GObjectsIter<'a> {
+    type Item = UObjectRef;
+
+    fn next(&mut self) -> Option<UObjectRef> {
+        for address in self.slots.by_ref().flatten() {
+            if address != 0 {
+                return Some(UObjectRef(address));
+            }
+        }
+
+        return None;
+    }
+}
+
+pub fn live_objects(mem: &dyn MemoryReader, gobjects: TArray) -> ReflectionResult<GObjectsIter<'_>> {
+    return Ok(GObjectsIter { slots: gobjects.iter(mem)? });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{TestAllocator, TestImage};
+
+    /**
+     * A TArray<u32> holding values with room for max, returns the header and data addresses
+     */
+    fn u32_array(image: &mut TestImage, values: &[u32], max: usize) -> (TArray<u32>, usize) {
+        let header: usize = image.alloc(TARRAY_SIZE);
+        let data: usize = image.alloc(max * 4);
+
+        for (idx, value) in values.iter().enumerate() {
+            image.mem.write_u32(data + (idx * 4), *value).unwrap();
+        }
+
+        image.write_array(header, data, values.len(), max);
+
+        return (TArray::new(header), data);
+    }
+
+    #[test]
+    fn array_headers_are_checked() {
+        let mut image: TestImage = TestImage::new();
+        let (array, data) = u32_array(&mut image, &[1, 2], 2);
+        let garbage: usize = image.alloc(TARRAY_SIZE);
+        image.write_array(garbage, data, 3, 2);
+
+        assert_eq!(array.to_vec(&image.mem), Ok(vec![1, 2]));
+        assert_eq!(array.iter(&image.mem).unwrap().collect::<ReflectionResult<Vec<u32>>>(), Ok(vec![1, 2]));
+        assert_eq!(array.get(&image.mem, 2), Err(ReflectionError::IndexOutOfRange { index: 2, count: 2 }));
+        assert_eq!(TArray::<u32>::new(garbage).len(&image.mem), Err(ReflectionError::InvalidArray { address: garbage, num: 3, max: 2 }));
+
+        array.set(&mut image.mem, 1, &7).unwrap();
+        array.truncate(&mut image.mem, 5).unwrap();
+
+        assert_eq!(array.to_vec(&image.mem), Ok(vec![1, 7]));
+
+        array.truncate(&mut image.mem, 1).unwrap();
+
+        assert_eq!(array.to_vec(&image.mem), Ok(vec![1]));
+        assert_eq!(array.capacity(&image.mem), Ok(2));
+    }
+
+    #[test]
+    fn full_arrays_grow_through_realloc() {
+        let mut image: TestImage = TestImage::new();
+        let allocator: TestAllocator = image.allocator(0x1000);
+        let header: usize = image.alloc(TARRAY_SIZE);
+        let array: TArray<u32> = TArray::new(header);
+        let values: Vec<u32> = (1..=18).collect();
+        image.write_array(header, 0, 0, 0);
+
+        array.push(&mut image.mem, &allocator, &values[0]).unwrap();
+
+        let data: usize = array.data(&image.mem).unwrap();
+
+        assert_eq!(array.capacity(&image.mem), Ok(grown_capacity(1)));
+        assert_eq!(*allocator.allocated.borrow(), vec![data]);
+
+        for value in values[1..].iter() {
+            array.push(&mut image.mem, &allocator, value).unwrap();
+        }
+
+        assert_eq!(array.to_vec(&image.mem), Ok(values));
+        assert_eq!(array.capacity(&image.mem), Ok(grown_capacity(18)));
+        assert_eq!(array.data(&image.mem), Ok(data));
+        assert_eq!(*allocator.reallocated.borrow(), vec![data]);
+        assert!(allocator.freed.borrow().is_empty());
+
+        array.assign(&mut image.mem, &allocator, &[4, 5, 6, 7]).unwrap();
+
+        assert_eq!(array.to_vec(&image.mem), Ok(vec![4, 5, 6, 7]));
+        assert_eq!(allocator.allocated.borrow().len(), 1);
+        assert_eq!(allocator.reallocated.borrow().len(), 1);
+    }
+
+    #[test]
+    fn failed_reallocs_leave_the_array_alone() {
+        let mut image: TestImage = TestImage::new();
+        let allocator: TestAllocator = image.allocator(0x1000);
+        let (array, data) = u32_array(&mut image, &[1, 2], 2);
+
+        assert!(matches!(array.push(&mut image.mem, &allocator, &3), Err(ReflectionError::AllocationFailed { .. })));
+        assert_eq!(array.to_vec(&image.mem), Ok(vec![1, 2]));
+        assert_eq!(array.data(&image.mem), Ok(data));
+        assert_eq!(array.capacity(&image.mem), Ok(2));
+    }
+
+    #[test]
+    fn path_names_follow_the_outer_chain() {
+        let mut image: TestImage = TestImage::new();
+        let game: usize = image.add_package("Game");
+        let world_class: usize = image.add_class("World", image.core, 0, 0x58);
+        let level_class: usize = image.add_class("Level", image.core, 0, 0x58);
+        let world: usize = image.add_object("TheWorld", game, world_class);
+        let level: usize = image.add_object("PersistentLevel", world, level_class);
+        let pawn: usize = image.add_object("Pawn_3", level, 0);
+        let looped: usize = image.add_object("Looped", 0, 0);
+        image.mem.write_usize(looped + UOBJECT_OUTER_OFFSET, looped).unwrap();
+
+        assert_eq!(UObjectRef(pawn).path_name(&image.mem, image.gnames), Ok("Game.TheWorld:PersistentLevel.Pawn_3".to_string()));
+        assert_eq!(UObjectRef(level).full_name(&image.mem, image.gnames), Ok("Level Game.TheWorld:PersistentLevel".to_string()));
+        assert_eq!(UObjectRef(level).class_path_name(&image.mem, image.gnames), Ok("Core.Level".to_string()));
+        assert_eq!(UObjectRef(looped).path_name(&image.mem, image.gnames), Err(ReflectionError::ChainTooLong { address: looped }));
+    }
+
+    #[test]
+    fn live_objects_skip_cleared_slots() {
+        let mut image: TestImage = TestImage::new();
+        let game: usize = image.add_package("Game");
+        let gone: usize = image.add_object("Gone", game, 0);
+        let kept: usize = image.add_object("Kept", game, 0);
+        image.remove_object(UObjectRef(gone).index(&image.mem).unwrap() as usize);
+
+        let objects: Vec<UObjectRef> = live_objects(&image.mem, image.gobjects).unwrap().collect();
+
+        assert!(objects.contains(&UObjectRef(kept)));
+        assert!(!objects.contains(&UObjectRef(gone)));
+        assert_eq!(objects.len(), image.gobjects.len(&image.mem).unwrap() - 1);
+    }
+}
@@ -0,0 +1,630 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::error::ReflectionResult;
+use crate::fields::{MAX_SUPER_DEPTH, UStructRef};
+use crate::flags::ObjectFlags;
+use crate::memory::MemoryReader;
+use crate::names::FName;
+use crate::path::ObjectPath;
+use crate::reflection::{TArray, UObjectRef};
+
+/**
+ * What the registry knows about a single GObjects slot
+ */
+pub struct RegistryEntry {
+    pub object: UObjectRef,
+    pub index: usize,
+    pub fname: FName,
+    /**
+     * Outer as of the last refresh, Rename can move an object without touching its slot or address
+     */
+    pub outer: Option<UObjectRef>,
+    pub path: String,
+    pub class: UObjectRef,
+    /**
+     * As of the last refresh
+     */
+    pub flags: ObjectFlags
+}
+
+/**
+ * How much changed during a refresh
+ */
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RefreshStats {
+    pub added: usize,
+    pub removed: usize,
+    /**
+     * Objects whose path changed because they or one of their outers were renamed or moved
+     */
+    pub renamed: usize
+}
+
+/**
+ * Index over GObjects by path name, by class and by address
+ * Built once, then refreshed incrementally, a refresh only rebuilds names for slots whose object actually changed
+ */
+pub struct ObjectRegistry {
+    slots: Vec<Option<RegistryEntry>>,
+    /**
+     * Keyed by the normalized path, every slot holding an object with that path in GObjects order
+     */
+    by_path: HashMap<String, Vec<usize>>,
+    by_class: HashMap<UObjectRef, BTreeSet<usize>>,
+    by_address: HashMap<usize, usize>,
+    /**
+     * SuperField of every registered UClass, so IsA checks never have to touch game memory
+     */
+    supers: HashMap<UObjectRef, Option<UObjectRef>>
+}
+
+impl Default for ObjectRegistry {
+    fn default() -> ObjectRegistry {
+        return ObjectRegistry::new();
+    }
+}
+
+impl ObjectRegistry {
+    pub fn new() -> ObjectRegistry {
+        return ObjectRegistry { slots: Vec::new(), by_path: HashMap::new(), by_class: HashMap::new(), by_address: HashMap::new(), supers: HashMap::new() };
+    }
+
+    pub fn build(mem: &dyn MemoryReader, gnames: TArray, gobjects: TArray) -> ReflectionResult<ObjectRegistry> {
+        let mut registry: ObjectRegistry = ObjectRegistry::new();
+        registry.refresh(mem, gnames, gobjects)?;
+        return Ok(registry);
+    }
+
+    /**
+     * Brings the registry in line with GObjects as it is right now
+     * A slot counts as unchanged when it holds the same address with the same FName and Outer, anything else is dropped and re-read
+     * When an object kept its address but changed name or outer, the paths of everything it contains are re-derived as well
+     * Flags are re-read for every object, objects get marked pending kill long before their slot is freed
+     * Slots that fail to read (objects still being constructed) are left out and retried on the next refresh
+     */
+    pub fn refresh(&mut self, mem: &dyn MemoryReader, gnames: TArray, gobjects: TArray) -> ReflectionResult<RefreshStats> {
+        let mut stats: RefreshStats = RefreshStats::default();
+
+        let addresses: Vec<usize> = read_slots(mem, gobjects)?;
+
+        for idx in addresses.len()..self.slots.len() {
+            if self.remove(idx) {
+                stats.removed += 1;
+            }
+        }
+
+        self.slots.truncate(addresses.len());
+        self.slots.resize_with(addresses.len(), || None);
+
+        let mut moved: bool = false;
+
+        for (idx, address) in addresses.into_iter().enumerate() {
+            let unchanged: bool = match &self.slots[idx] {
+                Some(entry) => entry.object.address() == address && UObjectRef(address).fname(mem).ok() == Some(entry.fname) && UObjectRef(address).outer(mem).ok() == Some(entry.outer),
+                None => address == 0
+            };
+
+            if unchanged {
+                if let Some(entry) = self.slots[idx].as_mut() {
+                    if let Ok(flags) = entry.object.flags(mem) {
+                        entry.flags = flags;
+                    }
+                }
+                continue;
+            }
+
+            if self.slots[idx].as_ref().is_some_and(|entry| entry.object.address() == address) {
+                moved = true;
+            }
+
+            if self.remove(idx) {
+                stats.removed += 1;
+            }
+
+            if address == 0 {
+                continue;
+            }
+
+            if let Ok(entry) = read_entry(mem, gnames, UObjectRef(address), idx) {
+                self.insert(entry);
+                stats.added += 1;
+            }
+        }
+
+        // Objects inside a renamed or moved one kept their own name and outer, only a fresh path name shows the change
+        if moved {
+            for idx in 0..self.slots.len() {
+                let object: UObjectRef = match &self.slots[idx] {
+                    Some(entry) if entry.object.path_name(mem, gnames).is_ok_and(|path| path != entry.path) => entry.object,
+                    _ => continue
+                };
+
+                self.remove(idx);
+
+                if let Ok(entry) = read_entry(mem, gnames, object, idx) {
+                    self.insert(entry);
+                    stats.renamed += 1;
+                }
+            }
+        }
+
+        let new_classes: Vec<UObjectRef> = self.find_by_class("Core.Class").iter().map(|entry| entry.object).filter(|class| !self.supers.contains_key(class)).collect();
+
+        for class in new_classes {
+            if let Ok(super_struct) = UStructRef(class).super_struct(mem) {
+                self.supers.insert(class, super_struct.map(|super_struct| super_struct.0));
+            }
+        }
+
+        return Ok(stats);
+    }
+
+    fn insert(&mut self, entry: RegistryEntry) {
+        let idx: usize = entry.index;
+
+        let indices: &mut Vec<usize> = self.by_path.entry(ObjectPath::normalize(&entry.path)).or_default();
+        let position: usize = indices.partition_point(|other| *other < idx);
+        indices.insert(position, idx);
+        self.by_class.entry(entry.class).or_default().insert(idx);
+        self.by_address.insert(entry.object.address(), idx);
+
+        self.slots[idx] = Some(entry);
+    }
+
+    fn remove(&mut self, idx: usize) -> bool {
+        let entry: RegistryEntry = match self.slots.get_mut(idx).and_then(|slot| slot.take()) {
+            Some(entry) => entry,
+            None => return false
+        };
+
+        let key: String = ObjectPath::normalize(&entry.path);
+
+        if let Some(indices) = self.by_path.get_mut(&key) {
+            indices.retain(|other| *other != idx);
+            if indices.is_empty() {
+                self.by_path.remove(&key);
+            }
+        }
+
+        if let Some(indices) = self.by_class.get_mut(&entry.class) {
+            indices.remove(&idx);
+            if indices.is_empty() {
+                self.by_class.remove(&entry.class);
+            }
+        }
+
+        if self.by_address.get(&entry.object.address()) == Some(&idx) {
+            self.by_address.remove(&entry.object.address());
+        }
+
+        self.supers.remove(&entry.object);
+
+        return true;
+    }
+
+    pub fn len(&self) -> usize {
+        return self.by_address.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.by_address.is_empty();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &RegistryEntry> {
+        return self.slots.iter().filter_map(|slot| slot.as_ref());
+    }
+
+    pub fn get_by_index(&self, idx: usize) -> Option<&RegistryEntry> {
+        return self.slots.get(idx)?.as_ref();
+    }
+
+    pub fn get_by_address(&self, address: usize) -> Option<&RegistryEntry> {
+        return self.get_by_index(*self.by_address.get(&address)?);
+    }
+
+    /**
+     * The first object in GObjects with this path, . and : are interchangeable between segments
+     */
+    pub fn get_by_path(&self, path: &str) -> Option<&RegistryEntry> {
+        return self.get_by_index(*self.by_path.get(&ObjectPath::normalize(path))?.first()?);
+    }
+
+    /**
+     * Every object with this path, paths are not unique while an object is being replaced by a new one of the same name
+     */
+    pub fn get_all_by_path(&self, path: &str) -> Vec<&RegistryEntry> {
+        return self.by_path.get(&ObjectPath::normalize(path)).into_iter().flat_map(|indices| indices.iter()).filter_map(|idx| self.get_by_index(*idx)).collect();
+    }
+
+    pub fn find_by_path(&self, path: &str) -> Option<UObjectRef> {
+        return self.get_by_path(path).map(|entry| entry.object);
+    }
+
+    /**
+     * Looks up an object by its full name (Class Package.Outer.Name), the class has to match as well
+     */
+    pub fn get_by_full_name(&self, full_name: &str) -> Option<&RegistryEntry> {
+        let path: ObjectPath = ObjectPath::parse(full_name)?;
+        let entry: &RegistryEntry = self.get_by_path(&path.path_name())?;
+
+        if let Some(class) = &path.class {
+            if !self.get_by_address(entry.class.address())?.path.rsplit('.').next()?.eq_ignore_ascii_case(class) {
+                return None;
+            }
+        }
+
+        return Some(entry);
+    }
+
+    /**
+     * Path name of the entry's class, as long as the class itself is registered
+     */
+    pub fn class_path(&self, entry: &RegistryEntry) -> Option<&str> {
+        return self.get_by_address(entry.class.address()).map(|class| class.path.as_str());
+    }
+
+    /**
+     * Every registered object whose class is exactly class, in GObjects order
+     */
+    pub fn objects_of_class(&self, class: UObjectRef) -> impl Iterator<Item = &RegistryEntry> {
+        return self.by_class.get(&class).into_iter().flat_map(|indices| indices.iter()).filter_map(move |idx| self.get_by_index(*idx));
+    }
+
+    /**
+     * Same as objects_of_class, but with the class given by its path name
+     */
+    pub fn find_by_class(&self, class_path: &str) -> Vec<&RegistryEntry> {
+        return match self.find_by_path(class_path) {
+            Some(class) => self.objects_of_class(class).collect(),
+            None => Vec::new()
+        };
+    }
+
+    /**
+     * Whether the entry is a class default object or archetype, or lives inside one (components of a Default__ object)
+     */
+    pub fn is_template(&self, entry: &RegistryEntry) -> bool {
+        if entry.flags.is_template() {
+            return true;
+        }
+
+        let path: ObjectPath = match ObjectPath::parse(&entry.path) {
+            Some(path) => path,
+            None => return false
+        };
+
+        let mut outer_path: String = String::new();
+
+        for outer in path.outers.iter() {
+            if !outer_path.is_empty() {
+                outer_path.push('.');
+            }
+            outer_path.push_str(outer);
+
+            if self.get_by_path(&outer_path).is_some_and(|outer_entry| outer_entry.flags.is_template()) {
+                return true;
+            }
+        }
+
+        return false;
+    }
+
+    /**
+     * Whether class is parent or inherits from it, following the SuperField chain of registered classes
+     */
+    pub fn is_subclass(&self, class: UObjectRef, parent: UObjectRef) -> bool {
+        let mut current: Option<UObjectRef> = Some(class);
+        let mut depth: usize = 0;
+
+        while let Some(ancestor) = current {
+            if ancestor == parent {
+                return true;
+            }
+
+            if depth > MAX_SUPER_DEPTH {
+                return false;
+            }
+
+            current = self.supers.get(&ancestor).copied().flatten();
+            depth += 1;
+        }
+
+        return false;
+    }
+
+    /**
+     * IsA, whether the entry is an instance of class or of any subclass of it
+     */
+    pub fn is_a(&self, entry: &RegistryEntry, class: UObjectRef) -> bool {
+        return self.is_subclass(entry.class, class);
+    }
+
+    /**
+     * Every registered class that is class or inherits from it
+     */
+    pub fn subclasses_of(&self, class: UObjectRef) -> Vec<&RegistryEntry> {
+        return self.find_by_class("Core.Class").into_iter().filter(|entry| self.is_subclass(entry.object, class)).collect();
+    }
+
+    /**
+     * Every registered object that IsA class, class default objects and archetypes included, in GObjects order
+     */
+    pub fn objects_of_subclasses(&self, class: UObjectRef) -> Vec<&RegistryEntry> {
+        let mut indices: BTreeSet<usize> = BTreeSet::new();
+
+        for subclass in self.subclasses_of(class) {
+            if let Some(class_indices) = self.by_class.get(&subclass.object) {
+                indices.extend(class_indices.iter());
+            }
+        }
+
+        return indices.into_iter().filter_map(|idx| self.get_by_index(idx)).collect();
+    }
+
+    /**
+     * Every registered object that IsA class and is an actual instance, leaving out Default__ objects, archetypes and their subobjects
+     */
+    pub fn instances_of(&self, class: UObjectRef) -> Vec<&RegistryEntry> {
+        return self.objects_of_subclasses(class).into_iter().filter(|entry| !self.is_template(entry)).collect();
+    }
+
+    /**
+     * The class followed by every super class that is registered, most derived first
+     */
+    pub fn class_chain(&self, class: UObjectRef) -> Vec<UObjectRef> {
+        let mut chain: Vec<UObjectRef> = Vec::new();
+        let mut current: Option<UObjectRef> = Some(class);
+
+        while let Some(ancestor) = current {
+            if chain.len() > MAX_SUPER_DEPTH {
+                break;
+            }
+
+            chain.push(ancestor);
+            current = self.supers.get(&ancestor).copied().flatten();
+        }
+
+        return chain;
+    }
+}
+
+fn read_entry(mem: &dyn MemoryReader, gnames: TArray, object: UObjectRef, idx: usize) -> ReflectionResult<RegistryEntry> {
+    return Ok(RegistryEntry {
+        object: object,
+        index: idx,
+        fname: object.fname(mem)?,
+        outer: object.outer(mem)?,
+        path: object.path_name(mem, gnames)?,
+        class: object.class(mem)?,
+        flags: object.flags(mem)?
+    });
+}
+
+/**
+ * Reads the whole GObjects pointer array, in one read when possible
+ */
+fn read_slots(mem: &dyn MemoryReader, gobjects: TArray) -> ReflectionResult<Vec<usize>> {
+    if let Ok(slots) = gobjects.to_vec(mem) {
+        return Ok(slots);
+    }
+
+    return Ok(gobjects.iter(mem)?.map(|slot| slot.unwrap_or(0)).collect());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fields::field_layout;
+    use crate::flags::{RF_ARCHETYPE_OBJECT, RF_BEGIN_DESTROYED, RF_CLASS_DEFAULT_OBJECT, RF_PENDING_KILL, RF_PENDING_KILL_MASK, RF_PUBLIC, RF_UNREACHABLE};
+    use crate::query::ObjectQuery;
+    use crate::memory::MemoryWriter;
+    use crate::reflection::uobject_layout;
+    use crate::testing::TestImage;
+
+    fn addresses(entries: Vec<&RegistryEntry>) -> Vec<usize> {
+        return entries.iter().map(|entry| entry.object.address()).collect();
+    }
+
+    #[test]
+    fn indexes_objects_by_path_class_and_address() {
+        let mut image: TestImage = TestImage::new();
+        let engine: usize = image.add_package("Engine");
+        let controller_class: usize = image.add_object("PlayerController", engine, image.class_class);
+        let controller: usize = image.add_object("PlayerController_2", engine, controller_class);
+        let registry: ObjectRegistry = ObjectRegistry::build(&image.mem, image.gnames, image.gobjects).unwrap();
+
+        assert_eq!(registry.len(), 6);
+        assert_eq!(registry.find_by_path("Engine.PlayerController_2"), Some(UObjectRef(controller)));
+        assert_eq!(registry.get_by_address(controller).unwrap().path, "Engine.PlayerController_2");
+        assert_eq!(registry.class_path(registry.get_by_address(controller).unwrap()), Some("Engine.PlayerController"));
+        assert_eq!(registry.find_by_class("Engine.PlayerController").len(), 1);
+        assert_eq!(registry.get_by_full_name("PlayerController Engine.PlayerController_2").unwrap().object, UObjectRef(controller));
+        assert!(registry.get_by_full_name("Class Engine.PlayerController_2").is_none());
+    }
+
+    #[test]
+    fn subobject_paths_use_the_engine_delimiter() {
+        let mut image: TestImage = TestImage::new();
+        let level_class: usize = image.add_object("Level", image.core, image.class_class);
+        let world_class: usize = image.add_object("World", image.core, image.class_class);
+        let map: usize = image.add_package("Caverns_P");
+        let world: usize = image.add_object("TheWorld", map, world_class);
+        let level: usize = image.add_object("PersistentLevel", world, level_class);
+        let actor: usize = image.add_object("Pawn_0", level, level_class);
+        let registry: ObjectRegistry = ObjectRegistry::build(&image.mem, image.gnames, image.gobjects).unwrap();
+
+        assert_eq!(registry.get_by_address(level).unwrap().path, "Caverns_P.TheWorld:PersistentLevel");
+        assert_eq!(registry.get_by_address(actor).unwrap().path, "Caverns_P.TheWorld:PersistentLevel.Pawn_0");
+        assert_eq!(registry.find_by_path("Caverns_P.TheWorld:PersistentLevel"), Some(UObjectRef(level)));
+        assert_eq!(registry.find_by_path("Caverns_P.TheWorld.PersistentLevel.Pawn_0"), Some(UObjectRef(actor)));
+        assert_eq!(registry.get_by_full_name("Level Caverns_P.TheWorld:PersistentLevel").unwrap().object, UObjectRef(level));
+    }
+
+    #[test]
+    fn duplicate_paths_survive_removal() {
+        let mut image: TestImage = TestImage::new();
+        let engine: usize = image.add_package("Engine");
+        let first: usize = image.add_object("Camera", engine, image.class_class);
+        let second: usize = image.add_object("Camera", engine, image.class_class);
+        let mut registry: ObjectRegistry = ObjectRegistry::build(&image.mem, image.gnames, image.gobjects).unwrap();
+
+        assert_eq!(registry.get_all_by_path("Engine.Camera").len(), 2);
+        assert_eq!(registry.find_by_path("Engine.Camera"), Some(UObjectRef(first)));
+
+        image.remove_object(registry.get_by_address(first).unwrap().index);
+        let stats: RefreshStats = registry.refresh(&image.mem, image.gnames, image.gobjects).unwrap();
+
+        assert_eq!((stats.added, stats.removed), (0, 1));
+        assert_eq!(registry.find_by_path("Engine.Camera"), Some(UObjectRef(second)));
+    }
+
+    #[test]
+    fn refresh_picks_up_replaced_slots() {
+        let mut image: TestImage = TestImage::new();
+        let engine: usize = image.add_package("Engine");
+        let camera: usize = image.add_object("Camera", engine, image.class_class);
+        let mut registry: ObjectRegistry = ObjectRegistry::build(&image.mem, image.gnames, image.gobjects).unwrap();
+        let index: usize = registry.get_by_address(camera).unwrap().index;
+
+        let input: usize = image.add_object("PlayerInput", engine, image.class_class);
+        image.remove_object(index);
+        let stats: RefreshStats = registry.refresh(&image.mem, image.gnames, image.gobjects).unwrap();
+
+        assert_eq!((stats.added, stats.removed), (1, 1));
+        assert_eq!(registry.find_by_path("Engine.Camera"), None);
+        assert_eq!(registry.find_by_path("Engine.PlayerInput"), Some(UObjectRef(input)));
+    }
+
+    #[test]
+    fn refresh_follows_moved_and_renamed_objects() {
+        let mut image: TestImage = TestImage::new();
+        let engine: usize = image.add_package("Engine");
+        let transient: usize = image.add_package("Transient");
+        let level: usize = image.add_object("PersistentLevel", engine, image.class_class);
+        let controller: usize = image.add_object("PlayerController", level, image.class_class);
+        let input: usize = image.add_object("PlayerInput", controller, image.class_class);
+        let mut registry: ObjectRegistry = ObjectRegistry::build(&image.mem, image.gnames, image.gobjects).unwrap();
+
+        // Rename(NewOuter=Transient) only rewrites Outer, the slot, address and FName stay the same
+        image.mem.write_usize(controller + uobject_layout().outer, transient).unwrap();
+        let stats: RefreshStats = registry.refresh(&image.mem, image.gnames, image.gobjects).unwrap();
+
+        assert_eq!((stats.added, stats.removed, stats.renamed), (1, 1, 1));
+        assert_eq!(registry.find_by_path("Engine.PersistentLevel.PlayerController"), None);
+        assert_eq!(registry.find_by_path("Transient.PlayerController"), Some(UObjectRef(controller)));
+        assert_eq!(registry.find_by_path("Engine.PersistentLevel.PlayerController.PlayerInput"), None);
+        assert_eq!(registry.find_by_path("Transient.PlayerController.PlayerInput"), Some(UObjectRef(input)));
+        assert_eq!(registry.get_by_address(controller).unwrap().outer, Some(UObjectRef(transient)));
+
+        // Renaming the level changes the path of everything still inside it
+        let fname: FName = image.fname("MainLevel");
+        image.mem.write_u32(level + uobject_layout().name, fname.index).unwrap();
+        image.mem.write_usize(controller + uobject_layout().outer, level).unwrap();
+        let stats: RefreshStats = registry.refresh(&image.mem, image.gnames, image.gobjects).unwrap();
+
+        assert_eq!((stats.added, stats.removed, stats.renamed), (2, 2, 1));
+        assert_eq!(registry.find_by_path("Engine.MainLevel.PlayerController.PlayerInput"), Some(UObjectRef(input)));
+        assert_eq!(registry.find_by_path("Transient.PlayerController.PlayerInput"), None);
+
+        let unchanged: RefreshStats = registry.refresh(&image.mem, image.gnames, image.gobjects).unwrap();
+        assert_eq!((unchanged.added, unchanged.removed, unchanged.renamed), (0, 0, 0));
+    }
+
+    #[test]
+    fn class_hierarchy_is_transitive() {
+        let mut image: TestImage = TestImage::new();
+        let engine: usize = image.add_package("Engine");
+        let poplar: usize = image.add_package("PoplarGame");
+        let controller_class: usize = image.add_class("PlayerController", engine, 0, 0x100);
+        let poplar_class: usize = image.add_class("PoplarPlayerController", poplar, controller_class, 0x100);
+        let sub_class: usize = image.add_class("SubPlayerController", poplar, poplar_class, 0x100);
+        let camera_class: usize = image.add_class("Camera", engine, 0, 0x100);
+
+        let default_sub: usize = image.add_object("Default__SubPlayerController", poplar, sub_class);
+        image.set_flags(default_sub, RF_CLASS_DEFAULT_OBJECT);
+        let default_input: usize = image.add_object("PlayerInput_0", default_sub, camera_class);
+        let poplar_controller: usize = image.add_object("PoplarPlayerController_0", poplar, poplar_class);
+        let sub: usize = image.add_object("SubPlayerController_0", poplar, sub_class);
+        let camera: usize = image.add_object("Camera_0", poplar, camera_class);
+        let registry: ObjectRegistry = ObjectRegistry::build(&image.mem, image.gnames, image.gobjects).unwrap();
+        let entry = |address: usize| registry.get_by_address(address).unwrap();
+
+        assert!(registry.is_subclass(UObjectRef(sub_class), UObjectRef(controller_class)));
+        assert!(registry.is_subclass(UObjectRef(sub_class), UObjectRef(sub_class)));
+        assert!(!registry.is_subclass(UObjectRef(controller_class), UObjectRef(sub_class)));
+        assert!(!registry.is_subclass(UObjectRef(camera_class), UObjectRef(controller_class)));
+
+        assert!(registry.is_a(entry(sub), UObjectRef(controller_class)));
+        assert!(registry.is_a(entry(sub), UObjectRef(poplar_class)));
+        assert!(!registry.is_a(entry(poplar_controller), UObjectRef(sub_class)));
+        assert!(!registry.is_a(entry(camera), UObjectRef(controller_class)));
+
+        assert_eq!(addresses(registry.subclasses_of(UObjectRef(controller_class))), vec![controller_class, poplar_class, sub_class]);
+        assert_eq!(addresses(registry.subclasses_of(UObjectRef(sub_class))), vec![sub_class]);
+        assert_eq!(registry.class_chain(UObjectRef(sub_class)), vec![UObjectRef(sub_class), UObjectRef(poplar_class), UObjectRef(controller_class)]);
+
+        assert_eq!(addresses(registry.objects_of_subclasses(UObjectRef(controller_class))), vec![default_sub, poplar_controller, sub]);
+        assert_eq!(addresses(registry.instances_of(UObjectRef(controller_class))), vec![poplar_controller, sub]);
+        assert_eq!(addresses(registry.instances_of(UObjectRef(camera_class))), vec![camera]);
+        assert!(registry.is_template(entry(default_input)));
+    }
+
+    #[test]
+    fn super_field_cycles_terminate() {
+        let mut image: TestImage = TestImage::new();
+        let engine: usize = image.add_package("Engine");
+        let first: usize = image.add_class("First", engine, 0, 0x100);
+        let second: usize = image.add_class("Second", engine, first, 0x100);
+        let unrelated: usize = image.add_class("Unrelated", engine, 0, 0x100);
+        image.mem.write_usize(first + field_layout().struct_super_field, second).unwrap();
+        let object: usize = image.add_object("First_0", engine, first);
+        let registry: ObjectRegistry = ObjectRegistry::build(&image.mem, image.gnames, image.gobjects).unwrap();
+
+        assert!(registry.is_subclass(UObjectRef(first), UObjectRef(second)));
+        assert!(!registry.is_subclass(UObjectRef(first), UObjectRef(unrelated)));
+        assert!(!registry.is_a(registry.get_by_address(object).unwrap(), UObjectRef(unrelated)));
+        assert_eq!(registry.class_chain(UObjectRef(first)).len(), MAX_SUPER_DEPTH + 1);
+        assert_eq!(addresses(registry.subclasses_of(UObjectRef(unrelated))), vec![unrelated]);
+    }
+
+    #[test]
+    fn templates_and_pending_kill_objects_are_filtered() {
+        let mut image: TestImage = TestImage::new();
+        let engine: usize = image.add_package("Engine");
+        let map: usize = image.add_package("Slums_P");
+        let camera_class: usize = image.add_class("Camera", engine, 0, 0x100);
+        let component_class: usize = image.add_class("CameraComponent", engine, 0, 0x100);
+
+        let default_camera: usize = image.add_object("Default__Camera", engine, camera_class);
+        image.set_flags(default_camera, RF_CLASS_DEFAULT_OBJECT | RF_PUBLIC);
+        let default_component: usize = image.add_object("CameraComponent_0", default_camera, component_class);
+        let archetype: usize = image.add_object("Camera_Archetype", engine, camera_class);
+        image.set_flags(archetype, RF_ARCHETYPE_OBJECT);
+        let live: usize = image.add_object("Camera_0", map, camera_class);
+        image.set_flags(live, RF_PUBLIC);
+        let killed: usize = image.add_object("Camera_1", map, camera_class);
+        image.set_flags(killed, RF_PENDING_KILL);
+        let destroyed: usize = image.add_object("Camera_2", map, camera_class);
+        image.set_flags(destroyed, RF_BEGIN_DESTROYED);
+        let unreachable: usize = image.add_object("Camera_3", map, camera_class);
+        image.set_flags(unreachable, RF_UNREACHABLE);
+        let live_component: usize = image.add_object("CameraComponent_0", live, component_class);
+        let registry: ObjectRegistry = ObjectRegistry::build(&image.mem, image.gnames, image.gobjects).unwrap();
+        let entry = |address: usize| registry.get_by_address(address).unwrap();
+
+        assert!(registry.is_template(entry(default_camera)));
+        assert!(registry.is_template(entry(default_component)));
+        assert!(registry.is_template(entry(archetype)));
+        assert!(!registry.is_template(entry(live)));
+        assert!(!registry.is_template(entry(live_component)));
+
+        let cameras: ObjectQuery = ObjectQuery::new().with_class("Engine.Camera");
+        assert_eq!(addresses(cameras.run(&registry)), vec![default_camera, archetype, live, killed, destroyed, unreachable]);
+        assert_eq!(addresses(cameras.clone().exclude_defaults().run(&registry)), vec![live, killed, destroyed, unreachable]);
+        assert_eq!(addresses(cameras.clone().without_flags(RF_PENDING_KILL_MASK).run(&registry)), vec![default_camera, archetype, live]);
+        assert_eq!(addresses(cameras.exclude_defaults().without_flags(RF_PENDING_KILL_MASK).run(&registry)), vec![live]);
+
+        let components: ObjectQuery = ObjectQuery::new().with_class("Engine.CameraComponent").exclude_defaults();
+        assert_eq!(addresses(components.run(&registry)), vec![live_component]);
+    }
+}
@@ -0,0 +1,569 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::error::{ReflectionError, ReflectionResult};
+use crate::fields::{PropertyInfo, UEnumRef, UStructRef};
+use crate::function::FunctionInfo;
+use crate::memory::MemoryReader;
+use crate::path::ObjectPath;
+use crate::property::{array_inner, byte_property_enum, class_property_meta_class, object_property_class, struct_property_struct, bool_property_mask};
+use crate::reflection::{TArray, UObjectRef};
+use crate::registry::{ObjectRegistry, RegistryEntry};
+
+/**
+ * Engine types every generated module refers to, written into mod.rs
+ */
+const PRELUDE: &str = "#![allow(non_camel_case_types, non_snake_case, dead_code)]
+
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FName {
+    pub index: u32,
+    pub number: u32
+}
+
+#[repr(C)]
+pub struct TArray<T> {
+    pub data: *mut T,
+    pub count: i32,
+    pub max: i32
+}
+
+pub type FString = TArray<u16>;
+
+#[repr(C)]
+pub struct FScriptDelegate {
+    pub object: usize,
+    pub function_name: FName
+}
+
+#[repr(C)]
+pub struct FScriptInterface {
+    pub object: usize,
+    pub interface: usize
+}
+";
+
+const MODULE_HEADER: &str = "#![allow(non_camel_case_types, non_snake_case, dead_code)]\nuse super::*;\n";
+
+const RUST_KEYWORDS: [&str; 51] = [
+    "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match",
+    "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+    "where", "while", "async", "await", "dyn", "abstract", "become", "box", "do", "final", "macro", "override", "priv", "typeof", "unsized",
+    "virtual", "yield", "try"
+];
+
+/**
+ * Generated source for one engine package, e.g. Engine becomes engine.rs
+ */
+pub struct SdkPackage {
+    pub module: String,
+    pub source: String
+}
+
+/**
+ * Emits Rust source for every UClass, UScriptStruct, UEnum and UFunction in the registry
+ * Classes and structs are flattened (inherited properties included) into #[repr(C)] structs padded to the engine's offsets,
+ * every function gets a <Class>_<Function>_Params struct laid out the way ProcessEvent expects
+ * Works on any MemoryReader, so the registry can come from the live game (reborn sdk) or from a GameSnapshot (sdkgen)
+ */
+pub struct SdkGenerator<'a> {
+    mem: &'a dyn MemoryReader,
+    gnames: TArray,
+    registry: &'a ObjectRegistry,
+    /**
+     * Module and identifier every class, struct and enum is emitted under
+     */
+    types: HashMap<UObjectRef, (String, String)>,
+    /**
+     * Identifier of every function's params struct, in the module of the class declaring it
+     */
+    params: HashMap<UObjectRef, String>,
+    /**
+     * Structs and classes that failed to emit, fields of these types become byte arrays and pointers to them *mut u8
+     */
+    skipped: HashMap<UObjectRef, ReflectionError>
+}
+
+impl<'a> SdkGenerator<'a> {
+    pub fn new(mem: &'a dyn MemoryReader, gnames: TArray, registry: &'a ObjectRegistry) -> SdkGenerator<'a> {
+        let mut generator: SdkGenerator = SdkGenerator { mem: mem, gnames: gnames, registry: registry, types: HashMap::new(), params: HashMap::new(), skipped: HashMap::new() };
+        let mut taken: HashMap<String, HashSet<String>> = HashMap::new();
+
+        for class_path in ["Core.Enum", "Core.ScriptStruct", "Core.Class"] {
+            for entry in registry.find_by_class(class_path) {
+                let path: ObjectPath = match ObjectPath::parse(&entry.path) {
+                    Some(path) => path,
+                    None => continue
+                };
+
+                let module: String = module_name(path.package());
+                let module_taken: &mut HashSet<String> = taken.entry(module.clone()).or_default();
+
+                // Structs declared inside different classes of the same package can share a name
+                let ident: String = if module_taken.contains(&sanitize_ident(&path.name)) {
+                    unique_ident(module_taken, &format!("{}_{}", path.outers.last().map(|outer| outer.as_str()).unwrap_or(""), path.name))
+                }
+                else {
+                    unique_ident(module_taken, &path.name)
+                };
+
+                generator.types.insert(entry.object, (module, ident));
+            }
+        }
+
+        // Params structs share the module with the types, so they are named once every type has its name
+        for entry in registry.find_by_class("Core.Class") {
+            let (module, ident) = match generator.types.get(&entry.object) {
+                Some(names) => names.clone(),
+                None => continue
+            };
+            let module_taken: &mut HashSet<String> = taken.entry(module).or_default();
+
+            for function in UStructRef(entry.object).children(mem).unwrap_or_default() {
+                if !function.class_name(mem, gnames).is_ok_and(|class_name| class_name == "Function") {
+                    continue;
+                }
+
+                if let Ok(name) = function.name(mem, gnames) {
+                    generator.params.insert(function, unique_ident(module_taken, &format!("{}_{}_Params", ident, name)));
+                }
+            }
+        }
+
+        return generator;
+    }
+
+    /**
+     * Source for every package, enums first, then structs, then classes each followed by their function params
+     * A struct or class that fails to emit can already be referenced by others, so everything is emitted again without it until nothing new fails
+     */
+    pub fn generate(&mut self) -> Vec<SdkPackage> {
+        loop {
+            let (packages, failed) = self.emit_packages();
+
+            if failed.is_empty() {
+                return packages;
+            }
+
+            self.skipped.extend(failed);
+        }
+    }
+
+    /**
+     * One pass over every type, along with the structs and classes that failed and were not skipped yet
+     */
+    fn emit_packages(&self) -> (Vec<SdkPackage>, Vec<(UObjectRef, ReflectionError)>) {
+        let mut sources: BTreeMap<String, String> = BTreeMap::new();
+        let mut failed: Vec<(UObjectRef, ReflectionError)> = Vec::new();
+
+        for entry in self.registry.find_by_class("Core.Enum") {
+            if let Some((module, ident)) = self.types.get(&entry.object) {
+                let source: &mut String = sources.entry(module.clone()).or_insert_with(|| MODULE_HEADER.to_string());
+
+                match self.emit_enum(UEnumRef(entry.object), ident, &entry.path) {
+                    Ok(code) => source.push_str(&code),
+                    Err(error) => source.push_str(&format!("\n// Skipped enum {}: {}\n", entry.path, error))
+                }
+            }
+        }
+
+        for entry in self.registry.find_by_class("Core.ScriptStruct") {
+            if let Some((module, ident)) = self.types.get(&entry.object) {
+                let source: &mut String = sources.entry(module.clone()).or_insert_with(|| MODULE_HEADER.to_string());
+
+                if let Some(error) = self.skipped.get(&entry.object) {
+                    source.push_str(&format!("\n// Skipped struct {}: {}\n", entry.path, error));
+                    continue;
+                }
+
+                match self.emit_struct(UStructRef(entry.object), module, ident, &entry.path) {
+                    Ok(code) => source.push_str(&code),
+                    Err(error) => failed.push((entry.object, error))
+                }
+            }
+        }
+
+        for entry in self.registry.find_by_class("Core.Class") {
+            if let Some((module, ident)) = self.types.get(&entry.object) {
+                let source: &mut String = sources.entry(module.clone()).or_insert_with(|| MODULE_HEADER.to_string());
+
+                if let Some(error) = self.skipped.get(&entry.object) {
+                    source.push_str(&format!("\n// Skipped class {}: {}\n", entry.path, error));
+                    continue;
+                }
+
+                match self.emit_class(entry, module, ident) {
+                    Ok(code) => source.push_str(&code),
+                    Err(error) => failed.push((entry.object, error))
+                }
+            }
+        }
+
+        return (sources.into_iter().map(|(module, source)| SdkPackage { module: module, source: source }).collect(), failed);
+    }
+
+    fn emit_enum(&self, uenum: UEnumRef, ident: &str, path: &str) -> ReflectionResult<String> {
+        let names: Vec<String> = uenum.names(self.mem, self.gnames)?;
+        let mut code: String = format!("\n// {}\n", path);
+
+        if names.is_empty() || names.len() > 0x100 {
+            code.push_str(&format!("// {} values do not fit a byte enum, skipped\n", names.len()));
+            return Ok(code);
+        }
+
+        code.push_str("#[repr(u8)]\n#[derive(Clone, Copy, PartialEq, Eq, Debug)]\n");
+        code.push_str(&format!("pub enum {} {{\n", ident));
+
+        let mut taken: HashSet<String> = HashSet::new();
+
+        for (value, name) in names.iter().enumerate() {
+            code.push_str(&format!("    {} = {},\n", unique_ident(&mut taken, name), value));
+        }
+
+        code.push_str("}\n");
+
+        return Ok(code);
+    }
+
+    fn emit_struct(&self, ustruct: UStructRef, module: &str, ident: &str, path: &str) -> ReflectionResult<String> {
+        let mut code: String = format!("\n// {}\n", path);
+
+        if let Some(super_struct) = ustruct.super_struct(self.mem)? {
+            code.push_str(&format!("// Inherits from {}\n", super_struct.0.path_name(self.mem, self.gnames)?));
+        }
+
+        let size: usize = ustruct.property_size(self.mem)?.max(0) as usize;
+        let properties: Vec<PropertyInfo> = ustruct.properties(self.mem, self.gnames)?;
+
+        code.push_str(&self.emit_layout(ident, module, properties, size)?);
+
+        return Ok(code);
+    }
+
+    fn emit_class(&self, entry: &RegistryEntry, module: &str, ident: &str) -> ReflectionResult<String> {
+        let class: UStructRef = UStructRef(entry.object);
+        let mut code: String = self.emit_struct(class, module, ident, &entry.path)?;
+
+        for field in class.children(self.mem)? {
+            if field.class_name(self.mem, self.gnames)? != "Function" {
+                continue;
+            }
+
+            let function: FunctionInfo = FunctionInfo::read(self.mem, self.gnames, UStructRef(field))?;
+            let params_ident: String = match self.params.get(&field) {
+                Some(params_ident) => params_ident.clone(),
+                None => continue
+            };
+
+            code.push_str(&format!("\n// {}\n", field.path_name(self.mem, self.gnames)?));
+            code.push_str(&self.emit_layout(&params_ident, module, function.params, function.parms_size)?);
+        }
+
+        return Ok(code);
+    }
+
+    /**
+     * A #[repr(C)] struct with every property at its engine offset, gaps are filled with padding arrays
+     * Properties overlapping the previous one (bitfield bools sharing a word) are listed as comments only
+     * Const asserts follow the struct, so a layout Rust lays out differently from the engine fails to compile instead of reading the wrong bytes
+     */
+    fn emit_layout(&self, ident: &str, module: &str, mut properties: Vec<PropertyInfo>, size: usize) -> ReflectionResult<String> {
+        properties.sort_by_key(|property| property.offset);
+
+        let mut code: String = format!("#[repr(C)]\npub struct {} {{\n", ident);
+        let mut asserts: String = String::new();
+        let mut taken: HashSet<String> = HashSet::new();
+        let mut cursor: usize = 0;
+
+        for property in properties.iter() {
+            let comment: String = self.field_comment(property)?;
+
+            if property.offset < cursor {
+                code.push_str(&format!("    // 0x{:04x} {} {}\n", property.offset, property.name, comment));
+                continue;
+            }
+
+            if property.offset > cursor {
+                code.push_str(&format!("    pub _pad_0x{:04x}: [u8; 0x{:x}],\n", cursor, property.offset - cursor));
+            }
+
+            let mut rust_type: String = self.element_type(property, module)?;
+
+            if property.array_dim > 1 {
+                rust_type = format!("[{}; {}]", rust_type, property.array_dim);
+            }
+
+            let field: String = unique_ident(&mut taken, &property.name);
+
+            code.push_str(&format!("    pub {}: {}, // 0x{:04x} (0x{:04x}) {}\n", field, rust_type, property.offset, property.size(), comment));
+            asserts.push_str(&format!("const _: () = assert!(::core::mem::offset_of!({}, {}) == 0x{:x});\n", ident, field, property.offset));
+
+            cursor = property.offset + property.size();
+        }
+
+        if size > cursor {
+            code.push_str(&format!("    pub _pad_0x{:04x}: [u8; 0x{:x}],\n", cursor, size - cursor));
+        }
+
+        code.push_str("}\n");
+        // Rust rounds the size up to the struct's alignment, the engine's PropertySize need not be
+        code.push_str(&format!("const _: () = assert!(::core::mem::size_of::<{}>() == 0x{:x}usize.next_multiple_of(::core::mem::align_of::<{}>()));\n", ident, size, ident));
+        code.push_str(&asserts);
+
+        return Ok(code);
+    }
+
+    /**
+     * The property class, plus the bit for bools and the enum for enum bytes
+     */
+    fn field_comment(&self, property: &PropertyInfo) -> ReflectionResult<String> {
+        return Ok(match property.class_name.as_str() {
+            "BoolProperty" => format!("BoolProperty mask 0x{:x}", bool_property_mask(self.mem, property.property)?),
+            "ByteProperty" => match byte_property_enum(self.mem, property.property)? {
+                Some(uenum) => format!("ByteProperty {}", uenum.0.path_name(self.mem, self.gnames)?),
+                None => property.class_name.clone()
+            },
+            _ => property.class_name.clone()
+        });
+    }
+
+    /**
+     * Rust type of a single element of a property, referring to other modules through the glob import of mod.rs
+     */
+    fn element_type(&self, property: &PropertyInfo, module: &str) -> ReflectionResult<String> {
+        return Ok(match property.class_name.as_str() {
+            "IntProperty" => "i32".to_string(),
+            "FloatProperty" => "f32".to_string(),
+            "BoolProperty" => "u32".to_string(),
+            "ByteProperty" => "u8".to_string(),
+            "NameProperty" => "FName".to_string(),
+            "StrProperty" => "FString".to_string(),
+            "ObjectProperty" | "ComponentProperty" => self.pointer_type(object_property_class(self.mem, property.property)?, module),
+            "ClassProperty" => self.pointer_type(class_property_meta_class(self.mem, property.property)?, module),
+            "InterfaceProperty" => "FScriptInterface".to_string(),
+            "DelegateProperty" => "FScriptDelegate".to_string(),
+            "StructProperty" => {
+                let ustruct: UStructRef = struct_property_struct(self.mem, property.property)?;
+
+                match self.type_path(ustruct.0, module) {
+                    Some(path) => path,
+                    None => format!("[u8; 0x{:x}]", property.element_size)
+                }
+            }
+            "ArrayProperty" => {
+                let inner: PropertyInfo = PropertyInfo::read(self.mem, self.gnames, array_inner(self.mem, property.property)?)?;
+                format!("TArray<{}>", self.element_type(&inner, module)?)
+            }
+            _ => format!("[u8; 0x{:x}]", property.element_size)
+        });
+    }
+
+    fn pointer_type(&self, class: UObjectRef, module: &str) -> String {
+        return match self.type_path(class, module) {
+            Some(path) => format!("*mut {}", path),
+            None => "*mut u8".to_string()
+        };
+    }
+
+    /**
+     * Bare identifier for types in the same module, module::Ident for everything else, None for types that were not emitted
+     */
+    fn type_path(&self, object: UObjectRef, module: &str) -> Option<String> {
+        if self.skipped.contains_key(&object) {
+            return None;
+        }
+
+        let (type_module, ident) = self.types.get(&object)?;
+
+        if type_module == module {
+            return Some(ident.clone());
+        }
+
+        return Some(format!("{}::{}", type_module, ident));
+    }
+}
+
+/**
+ * Writes every package to <dir>/<module>.rs and a mod.rs holding the prelude and the module list
+ */
+pub fn write_sdk(packages: &[SdkPackage], dir: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+
+    let mut mod_source: String = PRELUDE.to_string();
+    mod_source.push('\n');
+
+    for package in packages {
+        fs::write(dir.join(format!("{}.rs", package.module)), &package.source)?;
+        mod_source.push_str(&format!("pub mod {};\n", package.module));
+    }
+
+    return fs::write(dir.join("mod.rs"), mod_source);
+}
+
+fn module_name(package: &str) -> String {
+    return sanitize_ident(&package.to_lowercase());
+}
+
+/**
+ * Turns an engine name into a valid Rust identifier, anything but letters, digits and _ becomes _
+ */
+fn sanitize_ident(name: &str) -> String {
+    let mut ident: String = name.chars().map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' }).collect();
+
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+
+    if RUST_KEYWORDS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+
+    return ident;
+}
+
+/**
+ * Sanitizes name and appends a counter if the identifier is already used in the same scope
+ */
+fn unique_ident(taken: &mut HashSet<String>, name: &str) -> String {
+    let base: String = sanitize_ident(name);
+    let mut ident: String = base.clone();
+    let mut counter: usize = 1;
+
+    while taken.contains(&ident) {
+        ident = format!("{}_{}", base, counter);
+        counter += 1;
+    }
+
+    taken.insert(ident.clone());
+
+    return ident;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestImage;
+
+    fn generate(image: &TestImage) -> Vec<SdkPackage> {
+        let registry: ObjectRegistry = ObjectRegistry::build(&image.mem, image.gnames, image.gobjects).unwrap();
+
+        return SdkGenerator::new(&image.mem, image.gnames, &registry).generate();
+    }
+
+    fn package_source(packages: &[SdkPackage], module: &str) -> String {
+        return packages.iter().find(|package| package.module == module).unwrap().source.clone();
+    }
+
+    /**
+     * Writes the SDK to a scratch directory and type checks it with rustc, which evaluates the offset_of and size_of asserts
+     */
+    fn assert_compiles(packages: &[SdkPackage], name: &str) {
+        let dir: std::path::PathBuf = std::env::temp_dir().join(format!("reborn_sdk_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        write_sdk(packages, &dir).unwrap();
+
+        let rustc: String = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+        let output = std::process::Command::new(rustc)
+            .args(["--edition", "2021", "--crate-type", "lib", "--crate-name", "sdk", "--emit", "metadata", "--out-dir"])
+            .arg(&dir)
+            .arg(dir.join("mod.rs"))
+            .output()
+            .unwrap();
+
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(output.status.success(), "generated SDK does not compile:\n{}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    #[test]
+    fn colliding_names_get_unique_idents() {
+        let mut image: TestImage = TestImage::new();
+        let game: usize = image.add_package("Game");
+        let script_struct: usize = image.field_class("ScriptStruct");
+        let actor: usize = image.add_class("Actor", game, 0, 0x58);
+        let other: usize = image.add_class("Other", game, 0, 0x58);
+        let pawn: usize = image.add_class("Pawn", game, actor, 0x58);
+        image.add_struct("Vector", actor, script_struct, 0, 0xC);
+        image.add_struct("Pawn_Vector", other, script_struct, 0, 0xC);
+        image.add_struct("Vector", pawn, script_struct, 0, 0xC);
+
+        let source: String = generate(&image).into_iter().find(|package| package.module == "game").unwrap().source;
+
+        assert!(source.contains("pub struct Vector {"));
+        assert!(source.contains("pub struct Pawn_Vector {"));
+        assert!(source.contains("pub struct Pawn_Vector_1 {"));
+    }
+
+    #[test]
+    fn layouts_are_padded_and_asserted() {
+        let mut image: TestImage = TestImage::new();
+        let game: usize = image.add_package("Game");
+        let actor: usize = image.add_class("Actor", game, 0, 0x64);
+        image.add_property(actor, "IntProperty", "Health", 0x58, 4, &[]);
+        image.add_property(actor, "FloatProperty", "Speed", 0x60, 4, &[]);
+        let function: usize = image.add_function(actor, "Jump", 0x4);
+        image.add_param(function, "FloatProperty", "Height", 0x0, 4, 0);
+
+        let packages: Vec<SdkPackage> = generate(&image);
+        let source: String = package_source(&packages, "game");
+
+        assert!(source.contains("    pub _pad_0x0000: [u8; 0x58],\n    pub Health: i32, // 0x0058 (0x0004) IntProperty\n    pub _pad_0x005c: [u8; 0x4],\n    pub Speed: f32,"));
+        assert!(source.contains("const _: () = assert!(::core::mem::size_of::<Actor>() == 0x64usize.next_multiple_of(::core::mem::align_of::<Actor>()));"));
+        assert!(source.contains("const _: () = assert!(::core::mem::offset_of!(Actor, Speed) == 0x60);"));
+        assert!(source.contains("pub struct Actor_Jump_Params {\n    pub Height: f32,"));
+        assert_compiles(&packages, "padded");
+    }
+
+    #[test]
+    fn bitfields_arrays_enums_and_other_packages_compile() {
+        let mut image: TestImage = TestImage::new();
+        let script_struct: usize = image.field_class("ScriptStruct");
+        let core: usize = image.core;
+        let object: usize = image.add_class("Object", core, 0, 0x58);
+        let vector: usize = image.add_struct("Vector", object, script_struct, 0, 0xC);
+        image.add_property(vector, "FloatProperty", "X", 0x0, 4, &[]);
+        image.add_property(vector, "FloatProperty", "Y", 0x4, 4, &[]);
+        image.add_property(vector, "FloatProperty", "Z", 0x8, 4, &[]);
+
+        let game: usize = image.add_package("Game");
+        let net_role: usize = image.add_enum("ENetRole", game, &["ROLE_None", "ROLE_SimulatedProxy", "ROLE_Authority"]);
+
+        // Neither of these can be emitted, their StructProperty has no struct
+        let broken: usize = image.add_struct("Broken", game, script_struct, 0, 0x8);
+        image.add_property(broken, "StructProperty", "Inner", 0x0, 0x8, &[]);
+        let ghost: usize = image.add_class("Ghost", game, 0, 0x60);
+        image.add_property(ghost, "StructProperty", "Inner", 0x58, 0x8, &[]);
+
+        let actor: usize = image.add_class("Actor", game, object, 0x98);
+        image.add_property(actor, "BoolProperty", "bHidden", 0x58, 4, &[0x1]);
+        image.add_property(actor, "BoolProperty", "bStatic", 0x58, 4, &[0x2]);
+        image.add_property(actor, "ByteProperty", "Role", 0x5C, 1, &[net_role]);
+        let values: usize = image.add_property(actor, "FloatProperty", "Values", 0x60, 4, &[]);
+        image.set_array_dim(values, 4);
+        image.add_property(actor, "StructProperty", "Location", 0x70, 0xC, &[vector]);
+        image.add_property(actor, "StructProperty", "Shape", 0x7C, 0x8, &[broken]);
+        image.add_property(actor, "ObjectProperty", "Target", 0x88, 8, &[ghost]);
+        image.add_property(actor, "ObjectProperty", "Owner", 0x90, 8, &[actor]);
+
+        let packages: Vec<SdkPackage> = generate(&image);
+        let source: String = package_source(&packages, "game");
+
+        assert!(source.contains("    pub bHidden: u32, // 0x0058 (0x0004) BoolProperty mask 0x1\n    // 0x0058 bStatic BoolProperty mask 0x2\n"));
+        assert!(source.contains("    pub Role: u8, // 0x005c (0x0001) ByteProperty Game.ENetRole\n    pub _pad_0x005d: [u8; 0x3],\n"));
+        assert!(source.contains("    pub Values: [f32; 4], // 0x0060 (0x0010) FloatProperty\n"));
+        assert!(source.contains("    pub Location: core::Vector, // 0x0070 (0x000c) StructProperty\n"));
+        assert!(source.contains("pub enum ENetRole {\n    ROLE_None = 0,"));
+
+        // Skipped types are still listed, fields of their type fall back to bytes
+        assert!(source.contains("// Skipped struct Game.Broken: "));
+        assert!(source.contains("// Skipped class Game.Ghost: "));
+        assert!(source.contains("    pub Shape: [u8; 0x8], // 0x007c (0x0008) StructProperty\n"));
+        assert!(source.contains("    pub Target: *mut u8, // 0x0088 (0x0008) ObjectProperty\n"));
+        assert!(source.contains("    pub Owner: *mut Actor, // 0x0090 (0x0008) ObjectProperty\n"));
+        assert!(package_source(&packages, "core").contains("pub struct Vector {"));
+
+        assert_compiles(&packages, "mixed");
+    }
+}
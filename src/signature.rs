@@ -0,0 +1,283 @@
+use std::fmt;
+use std::ops::Range;
+
+use crate::error::ReflectionResult;
+use crate::memory::MemoryReader;
+use crate::pe::{PeError, PeImage, PeLayout, read_mapped_image};
+
+/**
+ * A byte pattern with wildcards, written the way disassemblers show bytes, e.g. 48 8B 0D ?? ?? ?? ?? 48 8B 01
+ * ? and ?? both match any byte
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Signature {
+    bytes: Vec<Option<u8>>
+}
+
+/**
+ * What the address of a match means
+ * Match is the first matched byte, for signatures written from the start of a function
+ * RipRelative follows the first ?? ?? ?? ?? as the disp32 of the instruction it is in, for loads of globals (mov rax, [rip+disp32]) and calls (E8 rel32)
+ * trailing is how many bytes of that instruction come after the disp32, 0 unless it has an immediate, e.g. 1 for cmp byte ptr [rip+disp32], imm8
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Resolve {
+    Match,
+    RipRelative { trailing: usize }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SignatureError {
+    Invalid(String),
+    NotFound,
+    /**
+     * More than one match, the signature is too short to tell them apart
+     */
+    Ambiguous { count: usize },
+    /**
+     * RipRelative on a signature without four wildcards in a row, or the displacement leaves the address space
+     */
+    Unresolvable
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            SignatureError::Invalid(text) => write!(f, "{} is not a valid signature", text),
+            SignatureError::NotFound => write!(f, "signature not found"),
+            SignatureError::Ambiguous { count } => write!(f, "signature matches {} times", count),
+            SignatureError::Unresolvable => write!(f, "signature has no RIP-relative operand to follow")
+        };
+    }
+}
+
+impl Signature {
+    /**
+     * None if any token is not a hex byte or wildcard, or there are no tokens at all
+     */
+    pub fn parse(text: &str) -> Option<Signature> {
+        let mut bytes: Vec<Option<u8>> = Vec::new();
+
+        for token in text.split_whitespace() {
+            if token == "?" || token == "??" {
+                bytes.push(None);
+                continue;
+            }
+
+            if token.len() != 2 {
+                return None;
+            }
+
+            bytes.push(Some(u8::from_str_radix(token, 16).ok()?));
+        }
+
+        if bytes.is_empty() {
+            return None;
+        }
+
+        return Some(Signature { bytes: bytes });
+    }
+
+    /**
+     * Offset of the first match in haystack
+     */
+    pub fn find(&self, haystack: &[u8]) -> Option<usize> {
+        return haystack.windows(self.bytes.len()).position(|window| self.matches(window));
+    }
+
+    /**
+     * Offset of every match in haystack, matches may overlap
+     */
+    pub fn find_all(&self, haystack: &[u8]) -> Vec<usize> {
+        return haystack.windows(self.bytes.len()).enumerate().filter(|(_, window)| self.matches(window)).map(|(offset, _)| offset).collect();
+    }
+
+    fn matches(&self, window: &[u8]) -> bool {
+        return self.bytes.iter().zip(window.iter()).all(|(expected, actual)| expected.is_none_or(|expected| expected == *actual));
+    }
+
+    /**
+     * Offset of the first run of four wildcards, taken to be the disp32 of a RIP-relative instruction
+     * e.g. the ?? ?? ?? ?? of mov rcx, [rip+disp32] in 48 8B 0D ?? ?? ?? ??
+     */
+    pub fn displacement_offset(&self) -> Option<usize> {
+        return self.bytes.windows(4).position(|window| window.iter().all(|byte| byte.is_none()));
+    }
+
+    /**
+     * Finds the one match inside ranges of haystack (mapped at base) and resolves it to an address
+     * Ranges are offsets into haystack, usually the code sections of a module image
+     */
+    pub fn scan(&self, haystack: &[u8], base: usize, ranges: &[Range<usize>], resolve: Resolve) -> Result<usize, SignatureError> {
+        let mut found: Vec<usize> = Vec::new();
+
+        for range in ranges {
+            let range: Range<usize> = range.start.min(haystack.len())..range.end.min(haystack.len());
+            found.extend(self.find_all(&haystack[range.clone()]).into_iter().map(|offset| range.start + offset));
+        }
+
+        let offset: usize = match found.as_slice() {
+            [] => return Err(SignatureError::NotFound),
+            [offset] => *offset,
+            _ => return Err(SignatureError::Ambiguous { count: found.len() })
+        };
+
+        return match resolve {
+            Resolve::Match => Ok(base + offset),
+            Resolve::RipRelative { trailing } => {
+                let displacement_offset: usize = offset + self.displacement_offset().ok_or(SignatureError::Unresolvable)?;
+                resolve_rip_relative(haystack, base, displacement_offset, trailing).ok_or(SignatureError::Unresolvable)
+            }
+        };
+    }
+}
+
+/**
+ * Target of a RIP-relative disp32 at displacement_offset, RIP being the end of the instruction, trailing bytes after the displacement
+ */
+pub fn resolve_rip_relative(haystack: &[u8], base: usize, displacement_offset: usize, trailing: usize) -> Option<usize> {
+    let bytes: [u8; 4] = haystack.get(displacement_offset..displacement_offset.checked_add(4)?)?.try_into().ok()?;
+    let displacement: i32 = i32::from_le_bytes(bytes);
+    let next_instruction: usize = base.checked_add(displacement_offset)?.checked_add(4)?.checked_add(trailing)?;
+
+    return next_instruction.checked_add_signed(displacement as isize);
+}
+
+/**
+ * Offsets of the executable sections of a mapped image (offsets are RVAs), the whole image if the headers do not parse
+ */
+pub fn code_sections(image: &[u8]) -> Vec<Range<usize>> {
+    let whole_image: Range<usize> = 0..image.len();
+
+    return match PeImage::parse(image, PeLayout::Mapped).map(|pe| pe.code_sections()) {
+        Ok(sections) if !sections.is_empty() => sections,
+        _ => vec![whole_image]
+    };
+}
+
+/**
+ * A copy of a mapped module and where its code is, so every signature is matched against the same bytes
+ */
+pub struct ModuleImage {
+    pub base: usize,
+    pub image: Vec<u8>,
+    pub code: Vec<Range<usize>>
+}
+
+impl ModuleImage {
+    /**
+     * Copies SizeOfImage bytes from the module's base, pages that can not be read are left zeroed
+     */
+    pub fn read(mem: &dyn MemoryReader, base: usize) -> ReflectionResult<ModuleImage> {
+        return Ok(ModuleImage::from_bytes(base, read_mapped_image(mem, base)?));
+    }
+
+    /**
+     * An image that is already laid out the way the loader maps it, offsets being RVAs
+     */
+    pub fn from_bytes(base: usize, image: Vec<u8>) -> ModuleImage {
+        let code: Vec<Range<usize>> = code_sections(&image);
+
+        return ModuleImage { base: base, image: image, code: code };
+    }
+
+    /**
+     * The module's headers, for exports, imports and the build timestamp
+     */
+    pub fn pe(&self) -> Result<PeImage<'_>, PeError> {
+        return PeImage::parse(&self.image, PeLayout::Mapped);
+    }
+
+    /**
+     * Finds the one match of text in the module's code and resolves it
+     */
+    pub fn scan(&self, text: &str, resolve: Resolve) -> Result<usize, SignatureError> {
+        let signature: Signature = Signature::parse(text).ok_or_else(|| SignatureError::Invalid(text.to_string()))?;
+
+        return signature.scan(&self.image, self.base, &self.code, resolve);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /**
+     * Two loads of globals around a call, at base 0x140000000
+     * 0x10: mov rcx, [rip+0x100] -> 0x140000117
+     * 0x17: call rel32 -0x1C -> 0x140000000
+     * 0x1C: cmp byte ptr [rip+0x20], 0 -> 0x140000043
+     * 0x23: mov rcx, [rip+0x200] -> 0x14000022A
+     */
+    const BASE: usize = 0x140000000;
+
+    fn code() -> Vec<u8> {
+        let mut code: Vec<u8> = vec![0xCC; 0x10];
+        code.extend_from_slice(&[0x48, 0x8B, 0x0D, 0x00, 0x01, 0x00, 0x00]);
+        code.extend_from_slice(&[0xE8, 0xE4, 0xFF, 0xFF, 0xFF]);
+        code.extend_from_slice(&[0x80, 0x3D, 0x20, 0x00, 0x00, 0x00, 0x00]);
+        code.extend_from_slice(&[0x48, 0x8B, 0x0D, 0x00, 0x02, 0x00, 0x00]);
+        code.extend_from_slice(&[0xCC; 0x10]);
+
+        return code;
+    }
+
+    #[test]
+    fn signatures_parse_bytes_and_wildcards() {
+        let signature: Signature = Signature::parse("48 8b 0D ? ?? e8").unwrap();
+
+        assert_eq!(signature.bytes, vec![Some(0x48), Some(0x8B), Some(0x0D), None, None, Some(0xE8)]);
+        assert_eq!(Signature::parse(""), None);
+        assert_eq!(Signature::parse("48 8"), None);
+        assert_eq!(Signature::parse("48 8BC"), None);
+        assert_eq!(Signature::parse("48 ZZ"), None);
+        assert_eq!(Signature::parse("48 8B 0D ?? ?? ?? ??").unwrap().displacement_offset(), Some(3));
+        assert_eq!(Signature::parse("48 8B 0D ?? ??").unwrap().displacement_offset(), None);
+    }
+
+    #[test]
+    fn find_all_returns_every_match() {
+        let code: Vec<u8> = code();
+        let load: Signature = Signature::parse("48 8B 0D ?? ?? 00 00").unwrap();
+
+        assert_eq!(load.find_all(&code), vec![0x10, 0x23]);
+        assert_eq!(load.find(&code), Some(0x10));
+        assert_eq!(Signature::parse("CC CC").unwrap().find_all(&[0xCC, 0xCC, 0xCC]), vec![0, 1]);
+        assert!(Signature::parse("0F 0B").unwrap().find_all(&code).is_empty());
+    }
+
+    #[test]
+    fn scan_needs_exactly_one_match() {
+        let code: Vec<u8> = code();
+        let whole: Range<usize> = 0..code.len();
+        let tail: Range<usize> = 0x20..code.len();
+
+        assert_eq!(Signature::parse("0F 0B").unwrap().scan(&code, BASE, std::slice::from_ref(&whole), Resolve::Match), Err(SignatureError::NotFound));
+        assert_eq!(Signature::parse("48 8B 0D").unwrap().scan(&code, BASE, std::slice::from_ref(&whole), Resolve::Match), Err(SignatureError::Ambiguous { count: 2 }));
+        assert_eq!(Signature::parse("48 8B 0D").unwrap().scan(&code, BASE, std::slice::from_ref(&tail), Resolve::Match), Ok(BASE + 0x23));
+        assert_eq!(Signature::parse("E8 ?? ?? ?? ??").unwrap().scan(&code, BASE, std::slice::from_ref(&whole), Resolve::Match), Ok(BASE + 0x17));
+        assert_eq!(Signature::parse("E8").unwrap().scan(&code, BASE, std::slice::from_ref(&whole), Resolve::RipRelative { trailing: 0 }), Err(SignatureError::Unresolvable));
+    }
+
+    #[test]
+    fn scan_follows_rip_relative_operands() {
+        let code: Vec<u8> = code();
+        let whole: Range<usize> = 0..code.len();
+
+        assert_eq!(Signature::parse("48 8B 0D ?? ?? ?? ?? E8").unwrap().scan(&code, BASE, std::slice::from_ref(&whole), Resolve::RipRelative { trailing: 0 }), Ok(BASE + 0x117));
+        assert_eq!(Signature::parse("E8 ?? ?? ?? ?? 80 3D").unwrap().scan(&code, BASE, std::slice::from_ref(&whole), Resolve::RipRelative { trailing: 0 }), Ok(BASE));
+        assert_eq!(Signature::parse("80 3D ?? ?? ?? ?? 00").unwrap().scan(&code, BASE, std::slice::from_ref(&whole), Resolve::RipRelative { trailing: 1 }), Ok(BASE + 0x43));
+    }
+
+    #[test]
+    fn rip_relative_targets_count_from_the_end_of_the_instruction() {
+        let code: Vec<u8> = code();
+
+        assert_eq!(resolve_rip_relative(&code, BASE, 0x13, 0), Some(BASE + 0x117));
+        assert_eq!(resolve_rip_relative(&code, BASE, 0x1E, 1), Some(BASE + 0x43));
+        assert_eq!(resolve_rip_relative(&code, BASE, 0x18, 0), Some(BASE));
+        assert_eq!(resolve_rip_relative(&code, BASE, code.len() - 3, 0), None);
+        assert_eq!(resolve_rip_relative(&code, BASE, usize::MAX, 0), None);
+        assert_eq!(resolve_rip_relative(&[0x00, 0x00, 0x00, 0x80], 0x10, 0, 0), None);
+    }
+}
@@ -0,0 +1,184 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::error::ReflectionResult;
+use crate::fields::{UEnumRef, UStructRef};
+use crate::memory::{MemoryReader, SnapshotMemory};
+use crate::names::fname_entry_size;
+use crate::reflection::{TARRAY_SIZE, TArray, UObjectRef, live_objects};
+
+/**
+ * File layout: magic, GNames and GObjects addresses, region count, then every region as base, length and bytes, all numbers u64 little endian
+ */
+const SNAPSHOT_MAGIC: &[u8; 8] = b"RBSNAP01";
+
+/**
+ * Objects whose class claims less than this are read as a bare UObject
+ */
+const UOBJECT_SIZE: usize = 0x58;
+
+/**
+ * The part of the game's memory the reflection code reads, copied out so dumps and the SDK can be generated without the game running
+ * GNames and GObjects keep their addresses, so everything read from the snapshot resolves exactly like it did in the game
+ */
+pub struct GameSnapshot {
+    pub memory: SnapshotMemory,
+    pub gnames: TArray,
+    pub gobjects: TArray
+}
+
+impl GameSnapshot {
+    /**
+     * Copies both tables, every name entry, every live object (as many bytes as its class says instances take) and every enum's names
+     * Objects and names that fail to read are left out, they read back as errors just like garbage slots do in the game
+     */
+    pub fn capture(mem: &dyn MemoryReader, gnames: TArray, gobjects: TArray) -> ReflectionResult<GameSnapshot> {
+        let mut memory: SnapshotMemory = SnapshotMemory::new();
+
+        for table in [gnames, gobjects] {
+            memory.capture_region(mem, table.address, TARRAY_SIZE);
+            memory.capture_region(mem, table.data(mem)?, table.len(mem)? * 0x8);
+        }
+
+        for entry in gnames.iter(mem)? {
+            let entry: usize = match entry {
+                Ok(entry) if entry != 0 => entry,
+                _ => continue
+            };
+
+            if let Ok(size) = fname_entry_size(mem, entry) {
+                memory.capture_region(mem, entry, size);
+            }
+        }
+
+        for object in live_objects(mem, gobjects)? {
+            let class: UObjectRef = match object.class(mem) {
+                Ok(class) => class,
+                Err(_) => continue
+            };
+            let size: usize = UStructRef(class).property_size(mem).map_or(0, |size| size.max(0) as usize);
+
+            if !memory.capture_region(mem, object.address(), size.max(UOBJECT_SIZE)) {
+                continue;
+            }
+
+            if class.name(mem, gnames).is_ok_and(|name| name == "Enum") {
+                let names: TArray<_> = UEnumRef(object).names_array();
+
+                if let (Ok(data), Ok(count)) = (names.data(mem), names.len(mem)) {
+                    memory.capture_region(mem, data, count * 0x8);
+                }
+            }
+        }
+
+        return Ok(GameSnapshot { memory: memory, gnames: gnames, gobjects: gobjects });
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = SNAPSHOT_MAGIC.to_vec();
+
+        bytes.extend_from_slice(&(self.gnames.address as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.gobjects.address as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.memory.regions().len() as u64).to_le_bytes());
+
+        for region in self.memory.regions() {
+            bytes.extend_from_slice(&(region.base as u64).to_le_bytes());
+            bytes.extend_from_slice(&(region.bytes.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(&region.bytes);
+        }
+
+        return bytes;
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<GameSnapshot> {
+        if !bytes.starts_with(SNAPSHOT_MAGIC) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a snapshot"));
+        }
+
+        let mut reader: SnapshotReader = SnapshotReader { bytes: bytes, offset: SNAPSHOT_MAGIC.len() };
+        let gnames: TArray = TArray::new(reader.number()?);
+        let gobjects: TArray = TArray::new(reader.number()?);
+        let region_count: usize = reader.number()?;
+        let mut memory: SnapshotMemory = SnapshotMemory::new();
+
+        for _ in 0..region_count {
+            let base: usize = reader.number()?;
+            let len: usize = reader.number()?;
+            memory.add_region(base, reader.take(len)?.to_vec());
+        }
+
+        return Ok(GameSnapshot { memory: memory, gnames: gnames, gobjects: gobjects });
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        return fs::write(path, self.to_bytes());
+    }
+
+    pub fn load(path: &Path) -> io::Result<GameSnapshot> {
+        return GameSnapshot::from_bytes(&fs::read(path)?);
+    }
+}
+
+struct SnapshotReader<'a> {
+    bytes: &'a [u8],
+    offset: usize
+}
+
+impl<'a> SnapshotReader<'a> {
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        let end: usize = self.offset.checked_add(len).filter(|end| *end <= self.bytes.len()).ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "snapshot is truncated"))?;
+        let bytes: &'a [u8] = &self.bytes[self.offset..end];
+
+        self.offset = end;
+
+        return Ok(bytes);
+    }
+
+    fn number(&mut self) -> io::Result<usize> {
+        let value: u64 = u64::from_le_bytes(self.take(8)?.try_into().unwrap());
+
+        return usize::try_from(value).map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "snapshot number out of range"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::names::get_fname_from_gnames_at_idx;
+    use crate::registry::ObjectRegistry;
+    use crate::testing::TestImage;
+
+    #[test]
+    fn captured_images_resolve_the_same() {
+        let mut image: TestImage = TestImage::new();
+        let game: usize = image.add_package("Game");
+        let class: usize = image.add_class("Widget", game, 0, 0x60);
+        image.add_property(class, "IntProperty", "Count", 0x58, 4, &[]);
+        let uenum: usize = image.add_enum("EMode", class, &["MODE_A", "MODE_B"]);
+        let wide: u32 = image.add_wide_name("Wide");
+        image.add_object_sized("Widget_0", game, class, 0x60);
+
+        let snapshot: GameSnapshot = GameSnapshot::capture(&image.mem, image.gnames, image.gobjects).unwrap();
+        let loaded: GameSnapshot = GameSnapshot::from_bytes(&snapshot.to_bytes()).unwrap();
+        let original: ObjectRegistry = ObjectRegistry::build(&image.mem, image.gnames, image.gobjects).unwrap();
+        let copy: ObjectRegistry = ObjectRegistry::build(&loaded.memory, loaded.gnames, loaded.gobjects).unwrap();
+
+        assert_eq!(loaded.gnames.address, image.gnames.address);
+        assert_eq!(copy.len(), original.len());
+        assert!(copy.get_by_path("Game.Widget.Count").is_some());
+        assert!(copy.get_by_path("Game.Widget_0").is_some());
+        assert_eq!(UEnumRef(UObjectRef(uenum)).names(&loaded.memory, loaded.gnames).unwrap(), vec!["MODE_A", "MODE_B"]);
+        assert_eq!(get_fname_from_gnames_at_idx(&loaded.memory, loaded.gnames, 0).unwrap(), "None");
+        assert_eq!(get_fname_from_gnames_at_idx(&loaded.memory, loaded.gnames, wide as usize).unwrap(), "Wide");
+    }
+
+    #[test]
+    fn truncated_files_are_rejected() {
+        let image: TestImage = TestImage::new();
+        let bytes: Vec<u8> = GameSnapshot::capture(&image.mem, image.gnames, image.gobjects).unwrap().to_bytes();
+
+        assert!(GameSnapshot::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(GameSnapshot::from_bytes(b"RBSNAP00").is_err());
+    }
+}
@@ -0,0 +1,423 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::error::{ReflectionError, ReflectionResult};
+use crate::fields::CPF_PARM;
+use crate::malloc::EngineAllocator;
+use crate::memory::{MemoryReader, MemoryWriter, SnapshotMemory};
+use crate::names::{FName, NameTable};
+use crate::reflection::TArray;
+
+/**
+ * Where hand built images start, every allocation gets its own region after this
+ */
+const IMAGE_BASE: usize = 0x10000000;
+const IMAGE_ALIGNMENT: usize = 0x10;
+
+/**
+ * How many entries the GNames and GObjects arrays of a test image can hold
+ */
+const TABLE_CAPACITY: usize = 0x400;
+
+const FNAME_ENTRY_SIZE: usize = 0x18 + 1024 * 2;
+const UOBJECT_SIZE: usize = 0x120;
+
+/**
+ * UField/UStruct/UProperty offsets of the original build, same as the default FieldLayout
+ */
+const UFIELD_NEXT_OFFSET: usize = 0x58;
+const USTRUCT_SUPERFIELD_OFFSET: usize = 0x70;
+const USTRUCT_CHILDREN_OFFSET: usize = 0x78;
+const USTRUCT_PROPERTYSIZE_OFFSET: usize = 0x80;
+const UPROPERTY_ARRAYDIM_OFFSET: usize = 0x60;
+const UPROPERTY_ELEMENTSIZE_OFFSET: usize = 0x64;
+const UPROPERTY_PROPERTYFLAGS_OFFSET: usize = 0x68;
+const UPROPERTY_OFFSET_OFFSET: usize = 0x88;
+const UPROPERTY_SIZE: usize = 0xC8;
+const UENUM_NAMES_OFFSET: usize = 0x60;
+const UFUNCTION_PARMSSIZE_OFFSET: usize = 0x102;
+
+/**
+ * A SnapshotMemory laid out the way the game lays out GNames, GObjects and UObjects, for host side unit tests
+ */
+pub struct TestImage {
+    pub mem: SnapshotMemory,
+    pub gnames: TArray,
+    pub gobjects: TArray,
+    /**
+     * Core, Core.Class and Core.Package, every image starts with them so packages and classes can be told apart
+     */
+    pub core: usize,
+    pub class_class: usize,
+    pub package_class: usize,
+    next: usize,
+    name_count: usize,
+    object_count: usize,
+    /**
+     * Field classes (IntProperty, Function, ...) created on first use
+     */
+    field_classes: HashMap<String, usize>
+}
+
+impl TestImage {
+    pub fn new() -> TestImage {
+        let mut image: TestImage = TestImage { mem: SnapshotMemory::new(), gnames: TArray::new(0), gobjects: TArray::new(0), core: 0, class_class: 0, package_class: 0, next: IMAGE_BASE, name_count: 0, object_count: 0, field_classes: HashMap::new() };
+
+        image.gnames = image.table();
+        image.gobjects = image.table();
+        image.add_name("None");
+
+        image.core = image.add_object("Core", 0, 0);
+        image.class_class = image.add_object("Class", image.core, 0);
+        image.package_class = image.add_object("Package", image.core, image.class_class);
+        image.mem.write_usize(image.core + 0x48, image.package_class).unwrap();
+        image.mem.write_i32(image.class_class + USTRUCT_PROPERTYSIZE_OFFSET, UOBJECT_SIZE as i32).unwrap();
+        image.mem.write_i32(image.package_class + USTRUCT_PROPERTYSIZE_OFFSET, UOBJECT_SIZE as i32).unwrap();
+
+        return image;
+    }
+
+    /**
+     * Maps size zeroed bytes at a fresh address
+     */
+    pub fn alloc(&mut self, size: usize) -> usize {
+        let address: usize = self.next;
+
+        self.mem.add_region(address, vec![0u8; size]);
+        self.next = (address + size + IMAGE_ALIGNMENT).next_multiple_of(IMAGE_ALIGNMENT);
+
+        return address;
+    }
+
+    fn table(&mut self) -> TArray {
+        let header: usize = self.alloc(0x10);
+        let data: usize = self.alloc(TABLE_CAPACITY * 8);
+
+        self.mem.write_usize(header, data).unwrap();
+        self.mem.write_u32(header + 0xC, TABLE_CAPACITY as u32).unwrap();
+
+        return TArray::new(header);
+    }
+
+    fn push(&mut self, table: TArray, count: usize, value: usize) {
+        let data: usize = table.data(&self.mem).unwrap();
+
+        self.mem.write_usize(data + (count * 8), value).unwrap();
+        self.mem.write_u32(table.address + 0x8, (count + 1) as u32).unwrap();
+    }
+
+    /**
+     * Appends an ANSI entry holding bytes as they are, returns its index
+     */
+    pub fn add_raw_name(&mut self, bytes: &[u8]) -> u32 {
+        let index: usize = self.name_count;
+        let entry: usize = self.alloc(FNAME_ENTRY_SIZE);
+
+        self.mem.write_u32(entry + 0x8, (index as u32) << 1).unwrap();
+        self.mem.write_bytes(entry + 0x18, bytes);
+        self.push(self.gnames, index, entry);
+        self.name_count = index + 1;
+
+        return index as u32;
+    }
+
+    pub fn add_name(&mut self, name: &str) -> u32 {
+        return self.add_raw_name(name.as_bytes());
+    }
+
+    pub fn add_wide_name(&mut self, name: &str) -> u32 {
+        let index: usize = self.name_count;
+        let entry: usize = self.alloc(FNAME_ENTRY_SIZE);
+        let units: Vec<u8> = name.encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect();
+
+        self.mem.write_u32(entry + 0x8, ((index as u32) << 1) | 1).unwrap();
+        self.mem.write_bytes(entry + 0x18, &units);
+        self.push(self.gnames, index, entry);
+        self.name_count = index + 1;
+
+        return index as u32;
+    }
+
+    /**
+     * FName for text, adding the base name if it is not there yet, a trailing _N becomes the instance number
+     */
+    pub fn fname(&mut self, text: &str) -> FName {
+        let (base, number) = match text.rsplit_once('_') {
+            Some((base, suffix)) if is_instance_suffix(suffix) => (base, suffix.parse::<u32>().unwrap() + 1),
+            _ => (text, 0)
+        };
+
+        let names: NameTable = NameTable::build(&self.mem, self.gnames).unwrap();
+
+        let index: u32 = match names.find(base) {
+            Some(index) => index,
+            None => self.add_name(base)
+        };
+
+        return FName { index: index, number: number };
+    }
+
+    /**
+     * Allocates a UObject of size bytes and registers it in GObjects, class 0 makes it its own class
+     */
+    pub fn add_object_sized(&mut self, name: &str, outer: usize, class: usize, size: usize) -> usize {
+        let fname: FName = self.fname(name);
+        let index: usize = self.object_count;
+        let object: usize = self.alloc(size.max(UOBJECT_SIZE));
+
+        self.mem.write_u32(object + 0x34, index as u32).unwrap();
+        self.mem.write_usize(object + 0x38, outer).unwrap();
+        self.mem.write_u32(object + 0x40, fname.index).unwrap();
+        self.mem.write_u32(object + 0x44, fname.number).unwrap();
+        self.mem.write_usize(object + 0x48, if class == 0 { object } else { class }).unwrap();
+        self.push(self.gobjects, index, object);
+        self.object_count = index + 1;
+
+        return object;
+    }
+
+    pub fn add_object(&mut self, name: &str, outer: usize, class: usize) -> usize {
+        return self.add_object_sized(name, outer, class, UOBJECT_SIZE);
+    }
+
+    /**
+     * Overwrites the ObjectFlags of an object, e.g. RF_CLASS_DEFAULT_OBJECT for a Default__ object
+     */
+    pub fn set_flags(&mut self, object: usize, flags: u64) {
+        self.mem.write_bytes(object + 0x10, &flags.to_le_bytes());
+    }
+
+    pub fn add_package(&mut self, name: &str) -> usize {
+        return self.add_object(name, 0, self.package_class);
+    }
+
+    /**
+     * A UClass (or any other UStruct when class is given) with no fields yet, instances are property_size bytes
+     */
+    pub fn add_struct(&mut self, name: &str, outer: usize, class: usize, super_struct: usize, property_size: usize) -> usize {
+        let ustruct: usize = self.add_object(name, outer, class);
+
+        self.mem.write_usize(ustruct + USTRUCT_SUPERFIELD_OFFSET, super_struct).unwrap();
+        self.mem.write_i32(ustruct + USTRUCT_PROPERTYSIZE_OFFSET, property_size as i32).unwrap();
+
+        return ustruct;
+    }
+
+    pub fn add_class(&mut self, name: &str, outer: usize, super_class: usize, property_size: usize) -> usize {
+        return self.add_struct(name, outer, self.class_class, super_class, property_size);
+    }
+
+    /**
+     * A UFunction declared on owner, its params are added with add_property on the function
+     */
+    pub fn add_function(&mut self, owner: usize, name: &str, parms_size: usize) -> usize {
+        let class: usize = self.field_class("Function");
+        let function: usize = self.add_object(name, owner, class);
+
+        self.mem.write_bytes(function + UFUNCTION_PARMSSIZE_OFFSET, &(parms_size as u16).to_le_bytes());
+        self.link_field(owner, function);
+
+        return function;
+    }
+
+    /**
+     * The Core class fields of class_name are instances of
+     */
+    pub fn field_class(&mut self, class_name: &str) -> usize {
+        if let Some(class) = self.field_classes.get(class_name) {
+            return *class;
+        }
+
+        let class: usize = self.add_class(class_name, self.core, 0, UOBJECT_SIZE);
+        self.field_classes.insert(class_name.to_string(), class);
+
+        return class;
+    }
+
+    /**
+     * Appends a field to the end of owner's Children list
+     */
+    pub fn link_field(&mut self, owner: usize, field: usize) {
+        let mut link: usize = owner + USTRUCT_CHILDREN_OFFSET;
+
+        loop {
+            let current: usize = self.mem.read_usize(link).unwrap();
+
+            if current == 0 {
+                break;
+            }
+
+            link = current + UFIELD_NEXT_OFFSET;
+        }
+
+        self.mem.write_usize(link, field).unwrap();
+    }
+
+    /**
+     * A single element UProperty of class_name declared on owner, extra is what the subclass stores right after UProperty (mask, enum, class, struct or inner)
+     */
+    pub fn add_property(&mut self, owner: usize, class_name: &str, name: &str, offset: usize, element_size: usize, extra: &[usize]) -> usize {
+        let class: usize = self.field_class(class_name);
+        let property: usize = self.add_object(name, owner, class);
+
+        self.mem.write_i32(property + UPROPERTY_ARRAYDIM_OFFSET, 1).unwrap();
+        self.mem.write_i32(property + UPROPERTY_ELEMENTSIZE_OFFSET, element_size as i32).unwrap();
+        self.mem.write_i32(property + UPROPERTY_OFFSET_OFFSET, offset as i32).unwrap();
+
+        for (idx, value) in extra.iter().enumerate() {
+            self.mem.write_usize(property + UPROPERTY_SIZE + (idx * 8), *value).unwrap();
+        }
+
+        self.link_field(owner, property);
+
+        return property;
+    }
+
+    /**
+     * Turns a property into a static array, e.g. 4 for FLOAT Values[4]
+     */
+    pub fn set_array_dim(&mut self, property: usize, array_dim: usize) {
+        self.mem.write_i32(property + UPROPERTY_ARRAYDIM_OFFSET, array_dim as i32).unwrap();
+    }
+
+    /**
+     * A function parameter, flags are the CPF_ flags on top of CPF_Parm
+     */
+    pub fn add_param(&mut self, function: usize, class_name: &str, name: &str, offset: usize, element_size: usize, flags: u64) -> usize {
+        let property: usize = self.add_property(function, class_name, name, offset, element_size, &[]);
+
+        self.mem.write_bytes(property + UPROPERTY_PROPERTYFLAGS_OFFSET, &(flags | CPF_PARM).to_le_bytes());
+
+        return property;
+    }
+
+    /**
+     * A property that is not linked into any struct, for the Inner of an ArrayProperty
+     */
+    pub fn add_inner_property(&mut self, outer: usize, class_name: &str, element_size: usize, extra: &[usize]) -> usize {
+        let class: usize = self.field_class(class_name);
+        let property: usize = self.add_object(&format!("{}_Inner", class_name), outer, class);
+
+        self.mem.write_i32(property + UPROPERTY_ARRAYDIM_OFFSET, 1).unwrap();
+        self.mem.write_i32(property + UPROPERTY_ELEMENTSIZE_OFFSET, element_size as i32).unwrap();
+
+        for (idx, value) in extra.iter().enumerate() {
+            self.mem.write_usize(property + UPROPERTY_SIZE + (idx * 8), *value).unwrap();
+        }
+
+        return property;
+    }
+
+    pub fn add_enum(&mut self, name: &str, outer: usize, values: &[&str]) -> usize {
+        let class: usize = self.field_class("Enum");
+        let uenum: usize = self.add_object(name, outer, class);
+        let data: usize = self.alloc(values.len().max(1) * 8);
+
+        for (idx, value) in values.iter().enumerate() {
+            let fname: FName = self.fname(value);
+            self.mem.write_u32(data + (idx * 8), fname.index).unwrap();
+            self.mem.write_u32(data + (idx * 8) + 4, fname.number).unwrap();
+        }
+
+        self.write_array(uenum + UENUM_NAMES_OFFSET, data, values.len(), values.len());
+
+        return uenum;
+    }
+
+    /**
+     * Fills in a TArray header
+     */
+    pub fn write_array(&mut self, address: usize, data: usize, num: usize, max: usize) {
+        self.mem.write_usize(address, data).unwrap();
+        self.mem.write_i32(address + 0x8, num as i32).unwrap();
+        self.mem.write_i32(address + 0xC, max as i32).unwrap();
+    }
+
+    /**
+     * An engine owned FString in a buffer of capacity code units
+     */
+    pub fn write_fstring(&mut self, address: usize, text: &str, capacity: usize) {
+        let units: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
+        let data: usize = self.alloc(capacity.max(units.len()) * 2);
+
+        self.mem.write_bytes(data, &units.iter().flat_map(|unit| unit.to_le_bytes()).collect::<Vec<u8>>());
+        self.write_array(address, data, units.len(), capacity.max(units.len()));
+    }
+
+    /**
+     * A heap of size bytes inside the image for a TestAllocator
+     */
+    pub fn allocator(&mut self, size: usize) -> TestAllocator {
+        let base: usize = self.alloc(size);
+
+        return TestAllocator { next: RefCell::new(base), end: base + size, allocated: RefCell::new(Vec::new()), reallocated: RefCell::new(Vec::new()), freed: RefCell::new(Vec::new()) };
+    }
+
+    /**
+     * Clears a GObjects slot the way the engine does when an object is destroyed
+     */
+    pub fn remove_object(&mut self, index: usize) {
+        let data: usize = self.gobjects.data(&self.mem).unwrap();
+
+        self.mem.write_usize(data + (index * 8), 0).unwrap();
+    }
+}
+
+fn is_instance_suffix(suffix: &str) -> bool {
+    return !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_digit()) && (suffix == "0" || !suffix.starts_with('0'));
+}
+
+/**
+ * Bump allocator standing in for GMalloc, remembers what was allocated and freed so tests can check for leaks
+ * Only the newest block can be reallocated, it grows in place since moving it would need the image to copy the contents
+ */
+pub struct TestAllocator {
+    next: RefCell<usize>,
+    end: usize,
+    pub allocated: RefCell<Vec<usize>>,
+    pub reallocated: RefCell<Vec<usize>>,
+    pub freed: RefCell<Vec<usize>>
+}
+
+impl TestAllocator {
+    /**
+     * Allocations that were never freed
+     */
+    pub fn live(&self) -> Vec<usize> {
+        return self.allocated.borrow().iter().filter(|address| !self.freed.borrow().contains(address)).copied().collect();
+    }
+}
+
+impl EngineAllocator for TestAllocator {
+    fn malloc(&self, size: usize, _alignment: u32) -> ReflectionResult<usize> {
+        let address: usize = *self.next.borrow();
+
+        if address + size > self.end {
+            return Err(ReflectionError::AllocationFailed { size: size });
+        }
+
+        *self.next.borrow_mut() = (address + size).next_multiple_of(IMAGE_ALIGNMENT);
+        self.allocated.borrow_mut().push(address);
+
+        return Ok(address);
+    }
+
+    fn realloc(&self, original: usize, size: usize, alignment: u32) -> ReflectionResult<usize> {
+        if original == 0 {
+            return self.malloc(size, alignment);
+        }
+
+        if self.allocated.borrow().last() != Some(&original) || self.freed.borrow().contains(&original) || original + size > self.end {
+            return Err(ReflectionError::AllocationFailed { size: size });
+        }
+
+        *self.next.borrow_mut() = (original + size).next_multiple_of(IMAGE_ALIGNMENT);
+        self.reallocated.borrow_mut().push(original);
+
+        return Ok(original);
+    }
+
+    fn free(&self, address: usize) {
+        self.freed.borrow_mut().push(address);
+    }
+}